@@ -1,3 +1,4 @@
+use graphity::node::PortInfo;
 use graphity::Node;
 
 #[derive(Default)]
@@ -34,6 +35,24 @@ impl Node<i32> for Sum {
     fn tick(&mut self) {
         self.producer = self.input1 + self.input2;
     }
+
+    fn consumers() -> Vec<PortInfo<Self::Consumer>> {
+        vec![
+            PortInfo::new(SumConsumer::In1, "in1").with_description("First summand"),
+            PortInfo::new(SumConsumer::In2, "in2").with_description("Second summand"),
+        ]
+    }
+
+    fn producers() -> Vec<PortInfo<Self::Producer>> {
+        vec![PortInfo::new(SumProducer, "out").with_description("Sum of both inputs")]
+    }
+
+    fn from_arguments(arguments: &[f32]) -> Option<Self> {
+        match arguments {
+            [] => Some(Self::default()),
+            _ => None,
+        }
+    }
 }
 
 pub struct Generator(i32);
@@ -42,6 +61,10 @@ impl Generator {
     pub fn new(value: i32) -> Self {
         Self(value)
     }
+
+    pub fn value(&self) -> i32 {
+        self.0
+    }
 }
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
@@ -57,6 +80,21 @@ impl Node<i32> for Generator {
     fn read(&self, _producer: Self::Producer) -> i32 {
         self.0
     }
+
+    fn producers() -> Vec<PortInfo<Self::Producer>> {
+        vec![PortInfo::new(GeneratorProducer, "out")]
+    }
+
+    fn from_arguments(arguments: &[f32]) -> Option<Self> {
+        match arguments {
+            [value] => Some(Self::new(*value as i32)),
+            _ => None,
+        }
+    }
+
+    fn arguments(&self) -> Vec<f32> {
+        vec![self.0 as f32]
+    }
 }
 
 #[derive(Default)]
@@ -103,4 +141,99 @@ impl Node<i32> for Recorder {
     fn write(&mut self, _consumer: Self::Consumer, input: i32) {
         self.0 = input;
     }
+
+    fn consumers() -> Vec<PortInfo<Self::Consumer>> {
+        vec![PortInfo::new(RecorderConsumer, "in")]
+    }
+
+    fn producers() -> Vec<PortInfo<Self::Producer>> {
+        vec![PortInfo::new(RecorderProducer, "out")]
+    }
+
+    fn from_arguments(arguments: &[f32]) -> Option<Self> {
+        match arguments {
+            [] => Some(Self::default()),
+            _ => None,
+        }
+    }
+}
+
+pub struct Mixer {
+    inputs: Vec<i32>,
+    producer: i32,
+}
+
+impl Mixer {
+    pub fn new(inputs: usize) -> Self {
+        Self {
+            inputs: vec![0; inputs],
+            producer: 0,
+        }
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn set_inputs(&mut self, inputs: usize) {
+        self.inputs.resize(inputs, 0);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MixerConsumer {
+    Input(usize),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MixerProducer;
+
+impl Node<i32> for Mixer {
+    type Consumer = MixerConsumer;
+    type Producer = MixerProducer;
+
+    fn write(&mut self, consumer: Self::Consumer, input: i32) {
+        match consumer {
+            Self::Consumer::Input(i) => {
+                if let Some(slot) = self.inputs.get_mut(i) {
+                    *slot = input;
+                }
+            }
+        }
+    }
+
+    fn read(&self, _producer: Self::Producer) -> i32 {
+        self.producer
+    }
+
+    fn tick(&mut self) {
+        self.producer = self.inputs.iter().sum();
+    }
+
+    fn offers_consumer(&self, consumer: Self::Consumer) -> bool {
+        match consumer {
+            Self::Consumer::Input(i) => i < self.inputs.len(),
+        }
+    }
+
+    fn offered_consumers(&self) -> Vec<PortInfo<Self::Consumer>> {
+        (0..self.inputs.len())
+            .map(|i| PortInfo::new(MixerConsumer::Input(i), "input").with_index(i))
+            .collect()
+    }
+
+    fn producers() -> Vec<PortInfo<Self::Producer>> {
+        vec![PortInfo::new(MixerProducer, "out")]
+    }
+
+    fn from_arguments(arguments: &[f32]) -> Option<Self> {
+        match arguments {
+            [inputs] => Some(Self::new(*inputs as usize)),
+            _ => None,
+        }
+    }
+
+    fn arguments(&self) -> Vec<f32> {
+        vec![self.inputs.len() as f32]
+    }
 }
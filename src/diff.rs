@@ -0,0 +1,213 @@
+//! Description of a target topology and its difference from the current graph.
+//!
+//! A [`Target`](struct.Target.html) lists nodes of the graph that should be
+//! kept, new nodes that should be added and all the edges between them. It can
+//! be compared with the current graph through
+//! [`SignalGraph::diff`](../signal/struct.SignalGraph.html#method.diff) and
+//! applied through
+//! [`SignalGraph::apply`](../signal/struct.SignalGraph.html#method.apply).
+//! Kept nodes are not touched, so they preserve their state.
+
+use alloc::vec::Vec;
+
+use crate::error::Error;
+use crate::node::{NodeClass, NodeIndex};
+
+/// Reference to a node of the target topology.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum NodeRef<NI> {
+    /// Node already present in the graph.
+    Existing(NI),
+    /// Node which is yet to be added, identified by its position in the list
+    /// of new nodes of the target.
+    New(usize),
+}
+
+/// An edge of the target topology.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Edge<NI>
+where
+    NI: NodeIndex,
+{
+    pub(crate) producer_node: NodeRef<NI>,
+    pub(crate) producer: NI::Producer,
+    pub(crate) consumer_node: NodeRef<NI>,
+    pub(crate) consumer: NI::Consumer,
+}
+
+impl<NI> Edge<NI>
+where
+    NI: NodeIndex,
+{
+    /// Node owning the producer of the edge.
+    pub fn producer_node(&self) -> NodeRef<NI> {
+        self.producer_node
+    }
+
+    /// Producer of the edge.
+    pub fn producer(&self) -> NI::Producer {
+        self.producer
+    }
+
+    /// Node owning the consumer of the edge.
+    pub fn consumer_node(&self) -> NodeRef<NI> {
+        self.consumer_node
+    }
+
+    /// Consumer of the edge.
+    pub fn consumer(&self) -> NI::Consumer {
+        self.consumer
+    }
+}
+
+/// Description of the desired topology of the graph.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut target = Target::new();
+/// let generator = target.keep_node(generator);
+/// let echo = target.add_node(Echo::default());
+/// target.add_edge(generator, GeneratorProducer, echo, EchoConsumer)?;
+/// graph.apply(target)?;
+/// ```
+pub struct Target<N, NI>
+where
+    NI: NodeIndex,
+{
+    pub(crate) kept_nodes: Vec<NI>,
+    pub(crate) new_nodes: Vec<N>,
+    pub(crate) edges: Vec<Edge<NI>>,
+}
+
+#[allow(clippy::new_without_default)]
+impl<N, NI> Target<N, NI>
+where
+    N: NodeClass<Class = NI::Class>,
+    NI: NodeIndex,
+{
+    pub fn new() -> Self {
+        Self {
+            kept_nodes: Vec::new(),
+            new_nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Keep a node currently present in the graph. Nodes which are not kept
+    /// will be removed.
+    pub fn keep_node(&mut self, node_index: NI) -> NodeRef<NI> {
+        if !self.kept_nodes.contains(&node_index) {
+            self.kept_nodes.push(node_index);
+        }
+        NodeRef::Existing(node_index)
+    }
+
+    /// Add a new node, which will be inserted into the graph.
+    pub fn add_node<IntoN>(&mut self, node: IntoN) -> NodeRef<NI>
+    where
+        IntoN: Into<N>,
+    {
+        self.new_nodes.push(node.into());
+        NodeRef::New(self.new_nodes.len() - 1)
+    }
+
+    /// Connect a producer of one node of the target to a consumer of another.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the nodes does not offer the given
+    /// producer or consumer, or if a new node is referenced which was not
+    /// added to this target.
+    pub fn add_edge<IntoP, IntoC>(
+        &mut self,
+        producer_node: NodeRef<NI>,
+        producer: IntoP,
+        consumer_node: NodeRef<NI>,
+        consumer: IntoC,
+    ) -> Result<(), Error>
+    where
+        IntoP: Into<NI::Producer>,
+        IntoC: Into<NI::Consumer>,
+    {
+        let producer = producer.into();
+        let consumer = consumer.into();
+        self.probe(producer_node)?.try_producer(producer)?;
+        self.probe(consumer_node)?.try_consumer(consumer)?;
+
+        let edge = Edge {
+            producer_node,
+            producer,
+            consumer_node,
+            consumer,
+        };
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+        Ok(())
+    }
+
+    /// All the edges of the target.
+    pub fn edges(&self) -> &[Edge<NI>] {
+        &self.edges
+    }
+
+    // Get an index of the same class as the referenced node, so it can be used
+    // to validate its ports.
+    fn probe(&self, node: NodeRef<NI>) -> Result<NI, Error> {
+        match node {
+            NodeRef::Existing(node_index) => Ok(node_index),
+            NodeRef::New(position) => self
+                .new_nodes
+                .get(position)
+                .map(|node| NI::new(node.class(), 0))
+                .ok_or(Error::NodeNotFound),
+        }
+    }
+}
+
+/// Changes needed to move from the current topology of the graph to a target.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Diff<NI, PI, CI>
+where
+    NI: NodeIndex,
+{
+    pub(crate) removed_nodes: Vec<NI>,
+    pub(crate) added_nodes: usize,
+    pub(crate) removed_edges: Vec<(PI, CI)>,
+    pub(crate) added_edges: Vec<Edge<NI>>,
+}
+
+impl<NI, PI, CI> Diff<NI, PI, CI>
+where
+    NI: NodeIndex,
+{
+    /// Nodes present in the graph which are not kept by the target.
+    pub fn removed_nodes(&self) -> &[NI] {
+        &self.removed_nodes
+    }
+
+    /// Number of new nodes of the target.
+    pub fn added_nodes(&self) -> usize {
+        self.added_nodes
+    }
+
+    /// Edges between kept nodes which are not present in the target. Edges of
+    /// removed nodes are not listed, they go away with their nodes.
+    pub fn removed_edges(&self) -> &[(PI, CI)] {
+        &self.removed_edges
+    }
+
+    /// Edges of the target which are not present in the graph.
+    pub fn added_edges(&self) -> &[Edge<NI>] {
+        &self.added_edges
+    }
+
+    /// Check whether the graph already matches the target.
+    pub fn is_empty(&self) -> bool {
+        self.removed_nodes.is_empty()
+            && self.added_nodes == 0
+            && self.removed_edges.is_empty()
+            && self.added_edges.is_empty()
+    }
+}
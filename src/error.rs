@@ -1,16 +1,56 @@
 //! Public interface of all the possible errors.
 
-use crate::signal::AddEdgeError;
+use core::fmt;
 
-impl From<AddEdgeError> for Error {
-    fn from(error: AddEdgeError) -> Self {
-        Self::AddEdgeError(error)
+use crate::signal::{AddEdgeError, AddEdgeErrorKind};
+
+impl<PI, CI> From<AddEdgeError<PI, CI>> for Error
+where
+    PI: Copy,
+    CI: Copy,
+{
+    fn from(error: AddEdgeError<PI, CI>) -> Self {
+        Self::AddEdgeError(error.kind())
     }
 }
 
 /// Convenience enumeration of all the errors that could be returned from
 /// libraries' public interfaces.
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Error {
-    AddEdgeError(AddEdgeError),
+    /// Adding an edge failed. Unlike
+    /// [`AddEdgeError`](../signal/enum.AddEdgeError.html), this does not carry
+    /// the indices of the refused edge.
+    AddEdgeError(AddEdgeErrorKind),
+    /// The node does not offer the requested consumer or producer.
+    InvalidPort,
+    /// The index does not reference any node in the graph.
+    NodeNotFound,
+    /// An input or an output of the same name is already present in the
+    /// graph.
+    DuplicateName,
+    /// The node does not offer a parameter of the given name.
+    UnknownParameter,
+    /// The value is out of the range of the parameter.
+    OutOfRange,
+    /// The index references a node which has been already removed from the
+    /// graph.
+    StaleIndex,
 }
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AddEdgeError(kind) => write!(f, "cannot add the edge, {}", kind),
+            Self::InvalidPort => write!(f, "the node does not offer such port"),
+            Self::NodeNotFound => write!(f, "the node is not present in the graph"),
+            Self::DuplicateName => write!(f, "the name is already taken"),
+            Self::UnknownParameter => write!(f, "the node does not offer such parameter"),
+            Self::OutOfRange => write!(f, "the value is out of the range of the parameter"),
+            Self::StaleIndex => write!(f, "the node has been already removed from the graph"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -33,6 +33,10 @@ where
     type Consumer = FeedbackSourceConsumer;
     type Producer = FeedbackSourceProducer;
 
+    fn reset(&mut self) {
+        *self.value.borrow_mut() = T::default();
+    }
+
     fn write(&mut self, _consumer: Self::Consumer, input: T) {
         *self.value.borrow_mut() = input;
     }
@@ -55,6 +59,10 @@ where
     type Consumer = FeedbackSinkConsumer;
     type Producer = FeedbackSinkProducer;
 
+    fn reset(&mut self) {
+        *self.value.borrow_mut() = T::default();
+    }
+
     fn read(&self, _producer: Self::Producer) -> T {
         (*self.value.borrow()).clone()
     }
@@ -86,4 +94,12 @@ mod tests {
         sink.tick();
         assert_eq!(sink.read(FeedbackSinkProducer), [10, 20]);
     }
+
+    #[test]
+    fn reset_shared_value() {
+        let (mut source, mut sink) = new_feedback_pair();
+        source.write(FeedbackSourceConsumer, 10);
+        sink.reset();
+        assert_eq!(sink.read(FeedbackSinkProducer), 0);
+    }
 }
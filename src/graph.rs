@@ -1,6 +1,7 @@
 use core::hash::Hash;
 use hashbrown::{HashMap, HashSet};
 
+use crate::error::Error;
 use crate::node::{ConsumerIndex, NodeClass, NodeIndex, ProducerIndex};
 
 pub struct Graph<N, NI, CI, PI>
@@ -46,11 +47,44 @@ where
         index
     }
 
-    pub fn remove_node(&mut self, node_index: NI) {
-        self.nodes.remove(&node_index);
+    // Insert a node under an index which was not issued by this graph, e.g.
+    // when loading a stored graph. Later added nodes get higher indices.
+    // Returns false if the index is already taken.
+    #[cfg(feature = "serde")]
+    pub fn restore_node(&mut self, node_index: NI, node: N) -> bool {
+        if self.nodes.contains_key(&node_index) {
+            return false;
+        }
+        self.index_counter = self.index_counter.max(node_index.index() + 1);
+        self.nodes.insert(node_index, node);
+        true
+    }
+
+    // Number of indices issued so far, including those of removed nodes.
+    #[cfg(feature = "serde")]
+    pub fn index_counter(&self) -> usize {
+        self.index_counter
+    }
+
+    // Make sure indices issued by a stored graph are not issued again.
+    #[cfg(feature = "serde")]
+    pub fn restore_index_counter(&mut self, index_counter: usize) {
+        self.index_counter = self.index_counter.max(index_counter);
+    }
+
+    pub fn insert_node(&mut self, node_index: NI, node: N) {
+        assert!(
+            node_index.index() < self.index_counter,
+            "Only previously issued indices can be reused"
+        );
+        self.nodes.insert(node_index, node);
+    }
+
+    pub fn remove_node(&mut self, node_index: NI) -> Option<N> {
         self.edges.retain(|(producer, consumer)| {
             producer.node_index() != node_index && consumer.node_index() != node_index
         });
+        self.nodes.remove(&node_index)
     }
 
     pub fn node(&self, node_index: &NI) -> Option<&N> {
@@ -61,6 +95,14 @@ where
         self.nodes.get_mut(node_index)
     }
 
+    pub fn missing_node_error(&self, node_index: &NI) -> Error {
+        if node_index.index() < self.index_counter {
+            Error::StaleIndex
+        } else {
+            Error::NodeNotFound
+        }
+    }
+
     pub fn add_edge(&mut self, producer: PI, consumer: CI) -> Result<(), AddEdgeError> {
         self.edges
             .iter()
@@ -82,7 +124,7 @@ where
         self.edges.remove(&(producer, consumer));
     }
 
-    pub fn has_edge(&mut self, producer: PI, consumer: CI) -> bool {
+    pub fn has_edge(&self, producer: PI, consumer: CI) -> bool {
         self.edges.contains(&(producer, consumer))
     }
 }
@@ -138,18 +180,22 @@ mod tests {
             Self { index }
         }
 
-        fn consumer<IntoC>(&self, consumer: IntoC) -> Self::ConsumerIndex
+        fn index(&self) -> usize {
+            self.index
+        }
+
+        fn try_consumer<IntoC>(&self, consumer: IntoC) -> Result<Self::ConsumerIndex, Error>
         where
             IntoC: Into<Self::Consumer>,
         {
-            CommonConsumerIndex::new(*self, consumer.into())
+            Ok(CommonConsumerIndex::new(*self, consumer.into()))
         }
 
-        fn producer<IntoP>(&self, producer: IntoP) -> TestProducerIndex
+        fn try_producer<IntoP>(&self, producer: IntoP) -> Result<TestProducerIndex, Error>
         where
             IntoP: Into<TestProducer>,
         {
-            CommonProducerIndex::new(*self, producer.into())
+            Ok(CommonProducerIndex::new(*self, producer.into()))
         }
     }
 
@@ -29,7 +29,7 @@
 /// * `Graph` defines the name of the generated signal graph type.
 /// * `<i32>` dictates the payload type that will flow between nodes.
 /// * `Generator` and `Echo` on the left hand side are identificators for each
-///    of the nodes. They must be unique within the graph.
+///   of the nodes. They must be unique within the graph.
 /// * Triplets on their right hand side reference a node and its associated
 ///   consumer and producer types. Read the [Node
 ///   documentation](node/trait.Node.html) to learn how to define these.
@@ -50,11 +50,59 @@
 /// # }
 /// ```
 ///
+/// Besides the graph, the macro generates a `__NodeClass` enumeration with one
+/// variant per registered node. It can be used to list consumers and producers
+/// described by [`Node::consumers`](node/trait.Node.html#method.consumers) and
+/// [`Node::producers`](node/trait.Node.html#method.producers) without
+/// instantiating the node:
+///
+/// ```ignore
+/// let ports = __NodeClass::Sum.consumers();
+/// ```
+///
+/// Classes can be also looked up by the name given to the macro.
+/// `__NodeClass::registry` collects all the classes into a
+/// [`Registry`](registry/struct.Registry.html), so nodes can be constructed
+/// from runtime strings through
+/// [`Node::from_arguments`](node/trait.Node.html#method.from_arguments) and
+/// their ports resolved through
+/// [`NodeWrapper::find_consumer`](node/trait.NodeWrapper.html#method.find_consumer),
+/// e.g. when loading a [patch](patch/index.html):
+///
+/// ```ignore
+/// let class = __NodeClass::from_name("Sum").unwrap();
+///
+/// let registry = __NodeClass::registry();
+/// let sum = registry.construct(class.name(), &[]).unwrap();
+/// let consumer = sum.find_consumer("in1", None).unwrap();
+/// let sum = graph.add_node(sum);
+/// graph.add_edge(generator.producer(GeneratorProducer), sum.consumer(consumer));
+/// ```
+///
+/// Attributes placed before the name of the graph are applied to all the
+/// generated types, i.e. `__Node`, `__NodeClass`, `__NodeIndex`, `__Consumer`
+/// and `__Producer`. With the `serde` feature enabled, this can be used to make
+/// the whole graph serializable, as long as all the registered nodes, consumers
+/// and producers implement `Serialize` and `Deserialize` too:
+///
+/// ```ignore
+/// graphity!(
+///     #[derive(Serialize, Deserialize)]
+///     Graph<i32>;
+///     Generator = {Generator, GeneratorConsumer, GeneratorProducer},
+///     Echo = {Echo, EchoConsumer, EchoProducer},
+/// );
+///
+/// let json = serde_json::to_string(&graph)?;
+/// let graph: Graph = serde_json::from_str(&json)?;
+/// ```
+///
 /// For more details on how to use such graph, see the [`SignalGraph`
 /// documentation](file:///home/phoracek/code/zlosynth/graphity/target/doc/graphity/signal/struct.SignalGraph.html).
 #[macro_export]
 macro_rules! graphity {
-    ( $graph:ident<$payload:ty>; $( $nid:ident = {$node:ty, $consumer:ty, $producer:ty} ),* $(,)? ) => {
+    ( $( #[$attr:meta] )* $graph:ident<$payload:ty>; $( $nid:ident = {$node:ty, $consumer:ty, $producer:ty} ),* $(,)? ) => {
+        $( #[$attr] )*
         pub enum __Node {
             $(
             $nid($node),
@@ -62,12 +110,96 @@ macro_rules! graphity {
         }
 
         #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+        $( #[$attr] )*
         pub enum __NodeClass {
             $(
             $nid,
             )*
         }
 
+        #[allow(dead_code)]
+        impl __NodeClass {
+            /// List all consumers offered by nodes of this class.
+            pub fn consumers(&self) -> graphity::__private::Vec<graphity::node::PortInfo<__Consumer>> {
+                match self {
+                    $(
+                    Self::$nid => <$node as graphity::node::Node<$payload>>::consumers()
+                        .into_iter()
+                        .map(|port| port.map(__Consumer::$nid))
+                        .collect(),
+                    )*
+                }
+            }
+
+            /// List all producers offered by nodes of this class.
+            pub fn producers(&self) -> graphity::__private::Vec<graphity::node::PortInfo<__Producer>> {
+                match self {
+                    $(
+                    Self::$nid => <$node as graphity::node::Node<$payload>>::producers()
+                        .into_iter()
+                        .map(|port| port.map(__Producer::$nid))
+                        .collect(),
+                    )*
+                }
+            }
+
+            /// Name of the class, as given to the macro.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(
+                    Self::$nid => stringify!($nid),
+                    )*
+                }
+            }
+
+            /// Find the class by its name.
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $(
+                    stringify!($nid) => Some(Self::$nid),
+                    )*
+                    _ => None,
+                }
+            }
+
+            /// Construct a node of this class from the given arguments.
+            ///
+            /// Nodes implementing `Default` are constructed through it when
+            /// no arguments are given and `Node::from_arguments` does not
+            /// accept an empty list.
+            pub fn construct(&self, arguments: &[f32]) -> Option<__Node> {
+                #[allow(unused_imports)]
+                use graphity::registry::{ViaDefault as _, WithoutDefault as _};
+
+                match self {
+                    $(
+                    Self::$nid => <$node as graphity::node::Node<$payload>>::from_arguments(arguments)
+                        .or_else(|| {
+                            if arguments.is_empty() {
+                                (&&graphity::registry::DefaultConstructor::<$node>::new())
+                                    .construct_default()
+                            } else {
+                                None
+                            }
+                        })
+                        .map(__Node::$nid),
+                    )*
+                }
+            }
+
+            /// Collect constructors of all the classes into a registry, keyed
+            /// by their names.
+            pub fn registry() -> graphity::registry::Registry<__Node> {
+                let mut registry = graphity::registry::Registry::new();
+                $(
+                registry.register(Self::$nid, stringify!($nid), |arguments| {
+                    Self::$nid.construct(arguments)
+                });
+                )*
+                registry
+            }
+        }
+
         impl graphity::node::NodeClass for __Node {
             type Class = __NodeClass;
 
@@ -93,7 +225,34 @@ macro_rules! graphity {
                 }
             }
 
+            fn reset(&mut self) {
+                match self {
+                    $(
+                    Self::$nid(node) => <$node as graphity::node::Node<$payload>>::reset(node),
+                    )*
+                }
+            }
+
             fn read<IntoP>(&self, producer: IntoP) -> $payload
+            where
+                IntoP: Into<Self::Producer>,
+            {
+                match self.try_read(producer) {
+                    Ok(output) => output,
+                    Err(_) => unreachable!("Node does not offer such producer"),
+                }
+            }
+
+            fn write<IntoC>(&mut self, consumer: IntoC, input: $payload)
+            where
+                IntoC: Into<Self::Consumer>,
+            {
+                if self.try_write(consumer, input).is_err() {
+                    unreachable!("Node does not offer such consumer");
+                }
+            }
+
+            fn try_read<IntoP>(&self, producer: IntoP) -> Result<$payload, graphity::Error>
             where
                 IntoP: Into<Self::Producer>,
             {
@@ -101,15 +260,214 @@ macro_rules! graphity {
                 match self {
                     $(
                     Self::$nid(node) => match producer {
-                        Self::Producer::$nid(producer) => <$node as graphity::node::Node<$payload>>::read(node, producer),
+                        Self::Producer::$nid(producer) => Ok(<$node as graphity::node::Node<$payload>>::read(node, producer)),
                         #[allow(unreachable_patterns)]
-                        _ => unreachable!("Node does not offer such producer"),
+                        _ => Err(graphity::Error::InvalidPort),
                     },
                     )*
                 }
             }
 
-            fn write<IntoC>(&mut self, consumer: IntoC, input: $payload)
+            fn try_write<IntoC>(&mut self, consumer: IntoC, input: $payload) -> Result<(), graphity::Error>
+            where
+                IntoC: Into<Self::Consumer>,
+            {
+                let consumer = consumer.into();
+                match self {
+                    $(
+                    Self::$nid(node) => match consumer {
+                        Self::Consumer::$nid(consumer) => {
+                            <$node as graphity::node::Node<$payload>>::write(node, consumer, input);
+                            Ok(())
+                        }
+                        #[allow(unreachable_patterns)]
+                        _ => Err(graphity::Error::InvalidPort),
+                    },
+                    )*
+                }
+            }
+
+            fn consumer_type<IntoC>(&self, consumer: IntoC) -> Option<graphity::node::PayloadType>
+            where
+                IntoC: Into<Self::Consumer>,
+            {
+                match consumer.into() {
+                    $(
+                    Self::Consumer::$nid(consumer) => <$node as graphity::node::Node<$payload>>::consumer_type(consumer),
+                    )*
+                }
+            }
+
+            fn producer_type<IntoP>(&self, producer: IntoP) -> Option<graphity::node::PayloadType>
+            where
+                IntoP: Into<Self::Producer>,
+            {
+                match producer.into() {
+                    $(
+                    Self::Producer::$nid(producer) => <$node as graphity::node::Node<$payload>>::producer_type(producer),
+                    )*
+                }
+            }
+
+            fn consumers(&self) -> graphity::__private::Vec<graphity::node::PortInfo<Self::Consumer>> {
+                match self {
+                    $(
+                    Self::$nid(node) => <$node as graphity::node::Node<$payload>>::offered_consumers(node)
+                        .into_iter()
+                        .map(|port| port.map(__Consumer::$nid))
+                        .collect(),
+                    )*
+                }
+            }
+
+            fn producers(&self) -> graphity::__private::Vec<graphity::node::PortInfo<Self::Producer>> {
+                match self {
+                    $(
+                    Self::$nid(node) => <$node as graphity::node::Node<$payload>>::offered_producers(node)
+                        .into_iter()
+                        .map(|port| port.map(__Producer::$nid))
+                        .collect(),
+                    )*
+                }
+            }
+
+            fn offers_consumer<IntoC>(&self, consumer: IntoC) -> bool
+            where
+                IntoC: Into<Self::Consumer>,
+            {
+                let consumer = consumer.into();
+                match self {
+                    $(
+                    Self::$nid(node) => match consumer {
+                        Self::Consumer::$nid(consumer) => <$node as graphity::node::Node<$payload>>::offers_consumer(node, consumer),
+                        #[allow(unreachable_patterns)]
+                        _ => false,
+                    },
+                    )*
+                }
+            }
+
+            fn offers_producer<IntoP>(&self, producer: IntoP) -> bool
+            where
+                IntoP: Into<Self::Producer>,
+            {
+                let producer = producer.into();
+                match self {
+                    $(
+                    Self::$nid(node) => match producer {
+                        Self::Producer::$nid(producer) => <$node as graphity::node::Node<$payload>>::offers_producer(node, producer),
+                        #[allow(unreachable_patterns)]
+                        _ => false,
+                    },
+                    )*
+                }
+            }
+
+            fn arguments(&self) -> graphity::__private::Vec<f32> {
+                match self {
+                    $(
+                    Self::$nid(node) => <$node as graphity::node::Node<$payload>>::arguments(node),
+                    )*
+                }
+            }
+
+            fn parameters(&self) -> Option<&dyn graphity::param::Parameters> {
+                match self {
+                    $(
+                    Self::$nid(node) => <$node as graphity::node::Node<$payload>>::parameters(node),
+                    )*
+                }
+            }
+
+            fn parameters_mut(&mut self) -> Option<&mut dyn graphity::param::Parameters> {
+                match self {
+                    $(
+                    Self::$nid(node) => <$node as graphity::node::Node<$payload>>::parameters_mut(node),
+                    )*
+                }
+            }
+
+            fn disconnect_policy<IntoC>(&self, consumer: IntoC) -> graphity::node::DisconnectPolicy<$payload>
+            where
+                IntoC: Into<Self::Consumer>,
+            {
+                let consumer = consumer.into();
+                match self {
+                    $(
+                    Self::$nid(node) => match consumer {
+                        Self::Consumer::$nid(consumer) => <$node as graphity::node::Node<$payload>>::disconnect_policy(node, consumer),
+                        #[allow(unreachable_patterns)]
+                        _ => graphity::node::DisconnectPolicy::Keep,
+                    },
+                    )*
+                }
+            }
+
+            fn on_connect<IntoC>(&mut self, consumer: IntoC)
+            where
+                IntoC: Into<Self::Consumer>,
+            {
+                let consumer = consumer.into();
+                match self {
+                    $(
+                    Self::$nid(node) => match consumer {
+                        Self::Consumer::$nid(consumer) => <$node as graphity::node::Node<$payload>>::on_connect(node, consumer),
+                        #[allow(unreachable_patterns)]
+                        _ => (),
+                    },
+                    )*
+                }
+            }
+
+            fn on_disconnect<IntoC>(&mut self, consumer: IntoC)
+            where
+                IntoC: Into<Self::Consumer>,
+            {
+                let consumer = consumer.into();
+                match self {
+                    $(
+                    Self::$nid(node) => match consumer {
+                        Self::Consumer::$nid(consumer) => <$node as graphity::node::Node<$payload>>::on_disconnect(node, consumer),
+                        #[allow(unreachable_patterns)]
+                        _ => (),
+                    },
+                    )*
+                }
+            }
+
+            fn on_connect_producer<IntoP>(&mut self, producer: IntoP)
+            where
+                IntoP: Into<Self::Producer>,
+            {
+                let producer = producer.into();
+                match self {
+                    $(
+                    Self::$nid(node) => match producer {
+                        Self::Producer::$nid(producer) => <$node as graphity::node::Node<$payload>>::on_connect_producer(node, producer),
+                        #[allow(unreachable_patterns)]
+                        _ => (),
+                    },
+                    )*
+                }
+            }
+
+            fn on_disconnect_producer<IntoP>(&mut self, producer: IntoP)
+            where
+                IntoP: Into<Self::Producer>,
+            {
+                let producer = producer.into();
+                match self {
+                    $(
+                    Self::$nid(node) => match producer {
+                        Self::Producer::$nid(producer) => <$node as graphity::node::Node<$payload>>::on_disconnect_producer(node, producer),
+                        #[allow(unreachable_patterns)]
+                        _ => (),
+                    },
+                    )*
+                }
+            }
+
+            fn on_delay_change<IntoC>(&mut self, consumer: IntoC, delayed: bool)
             where
                 IntoC: Into<Self::Consumer>,
             {
@@ -117,9 +475,9 @@ macro_rules! graphity {
                 match self {
                     $(
                     Self::$nid(node) => match consumer {
-                        Self::Consumer::$nid(consumer) => <$node as graphity::node::Node<$payload>>::write(node, consumer, input),
+                        Self::Consumer::$nid(consumer) => <$node as graphity::node::Node<$payload>>::on_delay_change(node, consumer, delayed),
                         #[allow(unreachable_patterns)]
-                        _ => unreachable!("Node does not offer such consumer"),
+                        _ => (),
                     },
                     )*
                 }
@@ -128,7 +486,8 @@ macro_rules! graphity {
 
         impl graphity::node::ExternalNodeWrapper<$payload> for __Node {}
 
-        #[derive(PartialEq, Eq, Copy, Clone, Hash)]
+        #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+        $( #[$attr] )*
         pub struct __NodeIndex {
             class: __NodeClass,
             index: usize,
@@ -145,7 +504,11 @@ macro_rules! graphity {
                 Self { class, index }
             }
 
-            fn consumer<IntoC>(&self, consumer: IntoC) -> __ConsumerIndex
+            fn index(&self) -> usize {
+                self.index
+            }
+
+            fn try_consumer<IntoC>(&self, consumer: IntoC) -> Result<__ConsumerIndex, graphity::Error>
             where
                 IntoC: Into<__Consumer>,
             {
@@ -153,17 +516,17 @@ macro_rules! graphity {
                 match self.class {
                     $(
                     Self::Class::$nid => match consumer {
-                        Self::Consumer::$nid(_) => <
+                        Self::Consumer::$nid(_) => Ok(<
                             graphity::node::CommonConsumerIndex<__NodeIndex> as graphity::node::ConsumerIndex
-                        >::new(*self, consumer),
+                        >::new(*self, consumer)),
                         #[allow(unreachable_patterns)]
-                        _ => panic!("Node does not offer such consumer")
+                        _ => Err(graphity::Error::InvalidPort),
                     },
                     )*
                 }
             }
 
-            fn producer<IntoP>(&self, producer: IntoP) -> __ProducerIndex
+            fn try_producer<IntoP>(&self, producer: IntoP) -> Result<__ProducerIndex, graphity::Error>
             where
                 IntoP: Into<__Producer>,
             {
@@ -171,11 +534,11 @@ macro_rules! graphity {
                 match self.class {
                     $(
                     Self::Class::$nid => match producer {
-                        Self::Producer::$nid(_) => <
+                        Self::Producer::$nid(_) => Ok(<
                             graphity::node::CommonProducerIndex<__NodeIndex> as graphity::node::ProducerIndex
-                        >::new(*self, producer),
+                        >::new(*self, producer)),
                         #[allow(unreachable_patterns)]
-                        _ => panic!("Node does not offer such producer")
+                        _ => Err(graphity::Error::InvalidPort),
                     },
                     )*
                 }
@@ -183,6 +546,7 @@ macro_rules! graphity {
         }
 
         #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+        $( #[$attr] )*
         pub enum __Consumer {
             $(
             $nid(<$node as graphity::node::Node<$payload>>::Consumer),
@@ -193,7 +557,8 @@ macro_rules! graphity {
 
         pub type __ConsumerIndex = graphity::node::CommonConsumerIndex<__NodeIndex>;
 
-        #[derive(PartialEq, Eq, Copy, Clone, Hash)]
+        #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+        $( #[$attr] )*
         pub enum __Producer {
             $(
             $nid(<$node as graphity::node::Node<$payload>>::Producer),
@@ -287,4 +652,942 @@ mod tests {
         graph.tick();
         assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 3);
     }
+
+    #[test]
+    fn list_ports() {
+        use graphity_nodes::*;
+
+        graphity!(
+            Graph<i32>;
+            Generator = {Generator, GeneratorConsumer, GeneratorProducer},
+            Sum = {Sum, SumConsumer, SumProducer},
+        );
+
+        let consumers = __NodeClass::Sum.consumers();
+        assert_eq!(consumers.len(), 2);
+        assert_eq!(*consumers[0].port(), __Consumer::Sum(SumConsumer::In1));
+        assert_eq!(consumers[0].name(), "in1");
+        assert_eq!(*consumers[1].port(), __Consumer::Sum(SumConsumer::In2));
+        assert!(__NodeClass::Generator.consumers().is_empty());
+
+        let mut graph = Graph::new();
+        let sum = graph.add_node(Sum::default());
+
+        let producers = graph.node(&sum).unwrap().producers();
+        assert_eq!(producers.len(), 1);
+        assert_eq!(producers[0].name(), "out");
+    }
+
+    #[test]
+    fn resolve_invalid_port() {
+        use graphity::Error;
+        use graphity_nodes::*;
+
+        graphity!(
+            Graph<i32>;
+            Generator = {Generator, GeneratorConsumer, GeneratorProducer},
+            Sum = {Sum, SumConsumer, SumProducer},
+        );
+
+        let mut graph = Graph::new();
+        let generator = graph.add_node(Generator::new(1));
+        let sum = graph.add_node(Sum::default());
+
+        assert!(generator.try_producer(GeneratorProducer).is_ok());
+        assert!(matches!(
+            generator.try_consumer(SumConsumer::In1),
+            Err(Error::InvalidPort)
+        ));
+        assert!(matches!(
+            sum.try_producer(GeneratorProducer),
+            Err(Error::InvalidPort)
+        ));
+    }
+
+    //
+    //      [Rec]
+    //        |
+    //     [Mixer]
+    //     /  |  \
+    //   [1] [2] [3]
+    //
+    #[test]
+    fn variable_number_of_ports() {
+        use graphity::signal::AddEdgeError;
+        use graphity_nodes::*;
+
+        graphity!(
+            Graph<i32>;
+            Generator = {Generator, GeneratorConsumer, GeneratorProducer},
+            Mixer = {Mixer, MixerConsumer, MixerProducer},
+            Recorder = {Recorder, RecorderConsumer, RecorderProducer},
+        );
+
+        let mut graph = Graph::new();
+        let mixer = graph.add_node(Mixer::new(3));
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            mixer.producer(MixerProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+        for i in 0..3 {
+            let generator = graph.add_node(Generator::new(i as i32 + 1));
+            graph.must_add_edge(
+                generator.producer(GeneratorProducer),
+                mixer.consumer(MixerConsumer::Input(i)),
+            );
+        }
+
+        let four = graph.add_node(Generator::new(4));
+        assert!(matches!(
+            graph.add_edge(
+                four.producer(GeneratorProducer),
+                mixer.consumer(MixerConsumer::Input(3)),
+            ),
+            Err(AddEdgeError::UnavailableConsumer { .. })
+        ));
+        assert_eq!(graph.node(&mixer).unwrap().consumers().len(), 3);
+
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 6);
+
+        graph.modify_node(mixer, |node| {
+            if let __Node::Mixer(mixer) = node {
+                mixer.set_inputs(2);
+            }
+        });
+
+        assert_eq!(graph.node(&mixer).unwrap().consumers().len(), 2);
+        assert!(!graph
+            .edges()
+            .any(|(_, consumer)| consumer == mixer.consumer(MixerConsumer::Input(2))));
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 3);
+
+        if let Some(__Node::Mixer(mixer)) = graph.node_mut(&mixer) {
+            mixer.set_inputs(1);
+        }
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
+        graph.refresh_ports(mixer);
+        assert_eq!(graph.edges().count(), 2);
+    }
+
+    #[test]
+    fn leave_unoffered_consumers_untouched_on_disconnect() {
+        use alloc::vec;
+        use alloc::vec::Vec;
+        use graphity::node::{DisconnectPolicy, Node};
+        use graphity_nodes::*;
+
+        pub struct Strict {
+            inputs: Vec<i32>,
+            patched: Vec<bool>,
+        }
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub enum StrictConsumer {
+            Input(usize),
+        }
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct StrictProducer;
+
+        impl Node<i32> for Strict {
+            type Consumer = StrictConsumer;
+            type Producer = StrictProducer;
+
+            fn write(&mut self, StrictConsumer::Input(i): Self::Consumer, input: i32) {
+                self.inputs[i] = input;
+            }
+
+            fn read(&self, _producer: Self::Producer) -> i32 {
+                self.inputs.iter().sum()
+            }
+
+            fn offers_consumer(&self, StrictConsumer::Input(i): Self::Consumer) -> bool {
+                i < self.inputs.len()
+            }
+
+            fn on_connect(&mut self, StrictConsumer::Input(i): Self::Consumer) {
+                self.patched[i] = true;
+            }
+
+            fn on_disconnect(&mut self, StrictConsumer::Input(i): Self::Consumer) {
+                self.patched[i] = false;
+            }
+        }
+
+        graphity!(
+            Graph<i32>;
+            Generator = {Generator, GeneratorConsumer, GeneratorProducer},
+            Strict = {Strict, StrictConsumer, StrictProducer},
+        );
+
+        let mut graph = Graph::new();
+        let strict = graph.add_node(Strict {
+            inputs: vec![0; 2],
+            patched: vec![false; 2],
+        });
+        for i in 0..2 {
+            let generator = graph.add_node(Generator::new(1));
+            graph.must_add_edge(
+                generator.producer(GeneratorProducer),
+                strict.consumer(StrictConsumer::Input(i)),
+            );
+            graph.set_disconnect_policy(
+                strict.consumer(StrictConsumer::Input(i)),
+                DisconnectPolicy::Reset,
+            );
+        }
+        graph.tick();
+
+        graph.modify_node(strict, |node| {
+            if let __Node::Strict(strict) = node {
+                strict.inputs.truncate(1);
+                strict.patched.truncate(1);
+            }
+        });
+        assert_eq!(graph.edges().count(), 1);
+        assert_eq!(graph.node(&strict).unwrap().read(StrictProducer), 1);
+    }
+
+    #[test]
+    fn pass_payloads_of_multiple_types() {
+        use core::convert::TryFrom;
+        use graphity::node::{Node, PayloadType};
+        use graphity::signal::AddEdgeError;
+
+        graphity::payload! {
+            pub enum Signal {
+                Audio(f32),
+                Gate(bool),
+            }
+        }
+
+        pub struct Clock(bool);
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct ClockConsumer;
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct ClockProducer;
+
+        impl Node<Signal> for Clock {
+            type Consumer = ClockConsumer;
+            type Producer = ClockProducer;
+
+            fn read(&self, _producer: Self::Producer) -> Signal {
+                self.0.into()
+            }
+
+            fn producer_type(_producer: Self::Producer) -> Option<PayloadType> {
+                Some(PayloadType::of::<bool>())
+            }
+        }
+
+        #[derive(Default)]
+        pub struct Meter(f32);
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct MeterConsumer;
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct MeterProducer;
+
+        impl Node<Signal> for Meter {
+            type Consumer = MeterConsumer;
+            type Producer = MeterProducer;
+
+            fn read(&self, _producer: Self::Producer) -> Signal {
+                self.0.into()
+            }
+
+            fn write(&mut self, _consumer: Self::Consumer, input: Signal) {
+                self.0 = f32::try_from(input).unwrap_or_default();
+            }
+
+            fn consumer_type(_consumer: Self::Consumer) -> Option<PayloadType> {
+                Some(PayloadType::of::<f32>())
+            }
+        }
+
+        graphity!(
+            Graph<Signal>;
+            Clock = {Clock, ClockConsumer, ClockProducer},
+            Meter = {Meter, MeterConsumer, MeterProducer},
+        );
+
+        let mut graph = Graph::new();
+        let clock = graph.add_node(Clock(true));
+        let meter = graph.add_node(Meter::default());
+        assert!(matches!(
+            graph.add_edge(clock.producer(ClockProducer), meter.consumer(MeterConsumer)),
+            Err(AddEdgeError::IncompatibleTypes { .. })
+        ));
+
+        graph.register_typed_conversion(|gate: bool| if gate { 1.0 } else { 0.0 });
+        graph.must_add_edge(clock.producer(ClockProducer), meter.consumer(MeterConsumer));
+        graph.tick();
+        assert_eq!(
+            graph.node(&meter).unwrap().read(MeterProducer),
+            Signal::Audio(1.0)
+        );
+    }
+
+    #[test]
+    fn export_graph_to_dot() {
+        use graphity_nodes::*;
+
+        graphity!(
+            Graph<i32>;
+            Generator = {Generator, GeneratorConsumer, GeneratorProducer},
+            Sum = {Sum, SumConsumer, SumProducer},
+        );
+
+        let mut graph = Graph::new();
+        let input = graph.add_input("in", None).unwrap();
+        let one = graph.add_node(Generator::new(1));
+        let sum = graph.add_node(Sum::default());
+        let inner = graph.add_node(Sum::default());
+        let output = graph.add_output("out", None).unwrap();
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In2),
+        );
+        graph.must_add_edge(inner.producer(SumProducer), sum.consumer(SumConsumer::In1));
+        graph.must_add_edge(
+            inner.producer(SumProducer),
+            inner.consumer(SumConsumer::In1),
+        );
+        graph
+            .connect_input(input, inner.consumer(SumConsumer::In2))
+            .unwrap();
+        graph
+            .connect_output(sum.producer(SumProducer), output)
+            .unwrap();
+
+        assert_eq!(
+            graph.to_dot(),
+            concat!(
+                "digraph {\n",
+                "    node [shape=record];\n",
+                "    n0 [shape=invhouse, label=\"in\"];\n",
+                "    n1 [label=\"{Generator #1|{<p0> out}}\"];\n",
+                "    n2 [label=\"{{<c0> in1|<c1> in2}|Sum #2|{<p0> out}}\"];\n",
+                "    n3 [label=\"{{<c0> in1|<c1> in2}|Sum #3|{<p0> out}}\"];\n",
+                "    n4 [shape=house, label=\"out\"];\n",
+                "    n0 -> n3:c1:n;\n",
+                "    n1:p0:s -> n2:c1:n;\n",
+                "    n2:p0:s -> n4;\n",
+                "    n3:p0:s -> n2:c0:n;\n",
+                "    n3:p0:s -> n3:c0:n [style=dashed, label=\"delayed\"];\n",
+                "}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn build_graph_from_patch() {
+        use alloc::string::ToString;
+        use alloc::vec;
+        use alloc::vec::Vec;
+        use graphity::patch::{NodeFactory, Patch, PatchErrorKind, Position};
+        use graphity_nodes::*;
+
+        graphity!(
+            Graph<i32>;
+            Generator = {Generator, GeneratorConsumer, GeneratorProducer},
+            Mixer = {Mixer, MixerConsumer, MixerProducer},
+            Recorder = {Recorder, RecorderConsumer, RecorderProducer},
+        );
+
+        struct Factory;
+
+        impl NodeFactory<__Node> for Factory {
+            fn contains(&self, class: &str) -> bool {
+                matches!(class, "Generator" | "Mixer" | "Recorder")
+            }
+
+            fn construct(&self, class: &str, arguments: &[f32]) -> Option<__Node> {
+                match (class, arguments) {
+                    ("Generator", [value]) => Some(Generator::new(*value as i32).into()),
+                    ("Mixer", [inputs]) => Some(Mixer::new(*inputs as usize).into()),
+                    ("Recorder", []) => Some(Recorder::default().into()),
+                    _ => None,
+                }
+            }
+
+            fn name(&self, node: &__Node) -> Option<&str> {
+                match node {
+                    __Node::Generator(_) => Some("Generator"),
+                    __Node::Mixer(_) => Some("Mixer"),
+                    __Node::Recorder(_) => Some("Recorder"),
+                }
+            }
+
+            fn arguments(&self, node: &__Node) -> Vec<f32> {
+                match node {
+                    __Node::Generator(generator) => vec![generator.value() as f32],
+                    __Node::Mixer(mixer) => vec![mixer.inputs() as f32],
+                    __Node::Recorder(_) => vec![],
+                }
+            }
+        }
+
+        let text = concat!(
+            "one = Generator(1)\n",
+            "two = Generator(2)\n",
+            "mix = Mixer(2)\n",
+            "rec = Recorder()\n",
+            "one.out -> mix.input[0]\n",
+            "two.out -> mix.input[1]\n",
+            "mix.out -> rec.in\n",
+        );
+        let (mut graph, nodes): (Graph, _) = Patch::parse(text).unwrap().build(&Factory).unwrap();
+        graph.tick();
+        assert_eq!(graph.node(&nodes["rec"]).unwrap().read(RecorderProducer), 3);
+        assert_eq!(
+            Patch::from_graph(&graph, &Factory, &nodes)
+                .unwrap()
+                .to_string(),
+            text
+        );
+
+        let mut invalid_names = nodes.clone();
+        invalid_names.insert("my rec".to_string(), invalid_names["rec"]);
+        invalid_names.remove("rec");
+        assert!(Patch::from_graph(&graph, &Factory, &invalid_names).is_none());
+
+        // Class `Gen1` at index 1 and class `Gen` at index 11 would both be
+        // named `gen11`.
+        struct Numbered;
+
+        impl NodeFactory<__Node> for Numbered {
+            fn contains(&self, class: &str) -> bool {
+                matches!(class, "Gen" | "Gen1")
+            }
+
+            fn construct(&self, class: &str, arguments: &[f32]) -> Option<__Node> {
+                match (class, arguments) {
+                    ("Gen" | "Gen1", [value]) => Some(Generator::new(*value as i32).into()),
+                    _ => None,
+                }
+            }
+
+            fn name(&self, node: &__Node) -> Option<&str> {
+                match node {
+                    __Node::Generator(generator) if generator.value() == 1 => Some("Gen1"),
+                    __Node::Generator(_) => Some("Gen"),
+                    _ => None,
+                }
+            }
+
+            fn arguments(&self, node: &__Node) -> Vec<f32> {
+                Factory.arguments(node)
+            }
+        }
+
+        let mut numbered = Graph::new();
+        for i in 0..12 {
+            numbered.add_node(Generator::new(if i == 1 { 1 } else { 2 }));
+        }
+        let text = Patch::from_graph(&numbered, &Numbered, &hashbrown::HashMap::new())
+            .unwrap()
+            .to_string();
+        assert!(Patch::parse(&text)
+            .unwrap()
+            .build(&Numbered)
+            .map(|(_, _): (Graph, _)| ())
+            .is_ok());
+
+        let build = |text| {
+            Patch::parse(text)
+                .unwrap()
+                .build(&Factory)
+                .map(|(_, _): (Graph, _)| ())
+        };
+
+        let error = build("one = Generator(1)\nosc = Oscillator(440)").unwrap_err();
+        assert_eq!(error.position(), Position { line: 2, column: 7 });
+        assert_eq!(
+            *error.kind(),
+            PatchErrorKind::UnknownClass("Oscillator".to_string())
+        );
+
+        let error =
+            build("one = Generator(1); rec = Recorder(); one.out -> rec.input").unwrap_err();
+        assert_eq!(
+            error.position(),
+            Position {
+                line: 1,
+                column: 54
+            }
+        );
+        assert_eq!(
+            *error.kind(),
+            PatchErrorKind::UnknownConsumer("input".to_string())
+        );
+
+        let error = build("mix = Mixer(1)\nrec = Recorder()\nrec.out -> mix.input[1]").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "3:16: the node does not offer consumer `input[1]`"
+        );
+    }
+
+    #[test]
+    fn construct_nodes_by_name() {
+        use alloc::string::ToString;
+        use graphity::patch::Patch;
+        use graphity_nodes::*;
+
+        graphity!(
+            Graph<i32>;
+            Generator = {Generator, GeneratorConsumer, GeneratorProducer},
+            Sum = {Sum, SumConsumer, SumProducer},
+            Mixer = {Mixer, MixerConsumer, MixerProducer},
+            Recorder = {Recorder, RecorderConsumer, RecorderProducer},
+            Echo = {Echo, EchoConsumer, EchoProducer},
+        );
+
+        assert_eq!(__NodeClass::from_name("Sum"), Some(__NodeClass::Sum));
+        assert_eq!(__NodeClass::Sum.name(), "Sum");
+        assert_eq!(__NodeClass::from_name("Oscillator"), None);
+
+        let registry = __NodeClass::registry();
+        assert!(registry.construct("Generator", &[]).is_none());
+        assert!(registry.construct("Echo", &[]).is_some());
+        assert!(registry.construct("Echo", &[1.0]).is_none());
+
+        let one = registry.construct("Generator", &[1.0]).unwrap();
+        assert_eq!(
+            one.find_producer("out", None),
+            Some(__Producer::Generator(GeneratorProducer))
+        );
+        let sum = registry.construct("Sum", &[]).unwrap();
+        assert_eq!(
+            sum.find_consumer("in2", None),
+            Some(__Consumer::Sum(SumConsumer::In2))
+        );
+        assert_eq!(sum.find_consumer("in3", None), None);
+        let consumer = sum.find_consumer("in1", None).unwrap();
+
+        let mut graph = Graph::new();
+        let one = graph.add_node(one);
+        let sum = graph.add_node(sum);
+        graph.must_add_edge(one.producer(GeneratorProducer), sum.consumer(consumer));
+        graph.tick();
+        assert_eq!(graph.node(&sum).unwrap().read(SumProducer), 1);
+
+        let text = concat!(
+            "one = Generator(1)\n",
+            "mix = Mixer(1)\n",
+            "rec = Recorder()\n",
+            "one.out -> mix.input[0]\n",
+            "mix.out -> rec.in\n",
+        );
+        let (graph, nodes): (Graph, _) = Patch::parse(text).unwrap().build(&registry).unwrap();
+        assert_eq!(
+            Patch::from_graph(&graph, &registry, &nodes)
+                .unwrap()
+                .to_string(),
+            text
+        );
+    }
+
+    #[test]
+    fn notify_nodes_about_connections() {
+        use graphity::node::Node;
+        use graphity_nodes::*;
+
+        #[derive(Default)]
+        pub struct Jack {
+            consumer_edges: i32,
+            producer_edges: i32,
+        }
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct JackConsumer;
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct JackProducer;
+
+        impl Node<i32> for Jack {
+            type Consumer = JackConsumer;
+            type Producer = JackProducer;
+
+            fn on_connect(&mut self, _consumer: Self::Consumer) {
+                self.consumer_edges += 1;
+            }
+
+            fn on_disconnect(&mut self, _consumer: Self::Consumer) {
+                self.consumer_edges -= 1;
+            }
+
+            fn on_connect_producer(&mut self, _producer: Self::Producer) {
+                self.producer_edges += 1;
+            }
+
+            fn on_disconnect_producer(&mut self, _producer: Self::Producer) {
+                self.producer_edges -= 1;
+            }
+        }
+
+        graphity!(
+            Graph<i32>;
+            Generator = {Generator, GeneratorConsumer, GeneratorProducer},
+            Jack = {Jack, JackConsumer, JackProducer},
+            Recorder = {Recorder, RecorderConsumer, RecorderProducer},
+        );
+
+        let edges = |graph: &Graph, jack| match graph.node(&jack) {
+            Some(__Node::Jack(jack)) => (jack.consumer_edges, jack.producer_edges),
+            _ => unreachable!(),
+        };
+
+        let mut graph = Graph::new();
+        let generator = graph.add_node(Generator::new(1));
+        let jack = graph.add_node(Jack::default());
+        let recorder1 = graph.add_node(Recorder::default());
+        let recorder2 = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            generator.producer(GeneratorProducer),
+            jack.consumer(JackConsumer),
+        );
+        graph.must_add_edge(
+            jack.producer(JackProducer),
+            recorder1.consumer(RecorderConsumer),
+        );
+        graph.must_add_edge(
+            jack.producer(JackProducer),
+            recorder2.consumer(RecorderConsumer),
+        );
+        assert_eq!(edges(&graph, jack), (1, 1));
+
+        graph.remove_edge(
+            jack.producer(JackProducer),
+            recorder1.consumer(RecorderConsumer),
+        );
+        assert_eq!(edges(&graph, jack), (1, 1));
+        graph.remove_node(recorder2);
+        assert_eq!(edges(&graph, jack), (1, 0));
+        graph.remove_node(generator);
+        assert_eq!(edges(&graph, jack), (0, 0));
+
+        let other = graph.add_node(Jack::default());
+        graph.must_add_edge(jack.producer(JackProducer), other.consumer(JackConsumer));
+        graph.must_add_edge(other.producer(JackProducer), jack.consumer(JackConsumer));
+        assert!(graph.is_feedback_edge(other.producer(JackProducer), jack.consumer(JackConsumer)));
+        assert_eq!(edges(&graph, jack), (1, 1));
+        assert_eq!(edges(&graph, other), (1, 1));
+
+        graph.remove_node(other);
+        assert_eq!(edges(&graph, jack), (0, 0));
+    }
+
+    #[test]
+    fn notify_nodes_about_feedback_rewiring() {
+        use graphity::node::Node;
+
+        #[derive(Default)]
+        pub struct Tap {
+            delayed: bool,
+        }
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct TapConsumer;
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct TapProducer;
+
+        impl Node<i32> for Tap {
+            type Consumer = TapConsumer;
+            type Producer = TapProducer;
+
+            fn on_delay_change(&mut self, _consumer: Self::Consumer, delayed: bool) {
+                self.delayed = delayed;
+            }
+        }
+
+        graphity!(
+            Graph<i32>;
+            Tap = {Tap, TapConsumer, TapProducer},
+        );
+
+        let delayed = |graph: &Graph, tap| match graph.node(&tap) {
+            Some(__Node::Tap(tap)) => tap.delayed,
+            _ => unreachable!(),
+        };
+
+        let mut graph = Graph::new();
+        let tap1 = graph.add_node(Tap::default());
+        let tap2 = graph.add_node(Tap::default());
+        graph.must_add_edge(tap1.producer(TapProducer), tap2.consumer(TapConsumer));
+        graph.must_add_edge(tap2.producer(TapProducer), tap1.consumer(TapConsumer));
+        assert!(delayed(&graph, tap1));
+        assert!(!delayed(&graph, tap2));
+
+        graph.set_feedback_preference(tap1.producer(TapProducer), tap2.consumer(TapConsumer), true);
+        assert!(!delayed(&graph, tap1));
+        assert!(delayed(&graph, tap2));
+
+        graph.remove_edge(tap2.producer(TapProducer), tap1.consumer(TapConsumer));
+        assert!(!delayed(&graph, tap2));
+    }
+
+    #[test]
+    fn apply_disconnect_policy_declared_by_node() {
+        use graphity::node::{DisconnectPolicy, Node};
+        use graphity_nodes::*;
+
+        #[derive(Default)]
+        pub struct Pitch(i32);
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct PitchConsumer;
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct PitchProducer;
+
+        impl Node<i32> for Pitch {
+            type Consumer = PitchConsumer;
+            type Producer = PitchProducer;
+
+            fn read(&self, _producer: Self::Producer) -> i32 {
+                self.0
+            }
+
+            fn write(&mut self, _consumer: Self::Consumer, input: i32) {
+                self.0 = input;
+            }
+
+            fn disconnect_policy(&self, _consumer: Self::Consumer) -> DisconnectPolicy<i32> {
+                DisconnectPolicy::Value(440)
+            }
+        }
+
+        graphity!(
+            Graph<i32>;
+            Generator = {Generator, GeneratorConsumer, GeneratorProducer},
+            Pitch = {Pitch, PitchConsumer, PitchProducer},
+        );
+
+        let mut graph = Graph::new();
+        let generator = graph.add_node(Generator::new(1));
+        let pitch = graph.add_node(Pitch::default());
+        graph.must_add_edge(
+            generator.producer(GeneratorProducer),
+            pitch.consumer(PitchConsumer),
+        );
+        graph.tick();
+        assert_eq!(graph.node(&pitch).unwrap().read(PitchProducer), 1);
+
+        graph.remove_edge(
+            generator.producer(GeneratorProducer),
+            pitch.consumer(PitchConsumer),
+        );
+        assert_eq!(graph.node(&pitch).unwrap().read(PitchProducer), 440);
+    }
+
+    #[test]
+    fn access_named_parameters() {
+        use alloc::vec;
+        use alloc::vec::Vec;
+        use graphity::node::Node;
+        use graphity::param::{ParamInfo, Parameters};
+        use graphity::Error;
+        use graphity_nodes::*;
+
+        #[derive(Default)]
+        pub struct Gain(f32);
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub enum GainConsumer {}
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub enum GainProducer {}
+
+        impl Node<i32> for Gain {
+            type Consumer = GainConsumer;
+            type Producer = GainProducer;
+
+            fn parameters(&self) -> Option<&dyn Parameters> {
+                Some(self)
+            }
+
+            fn parameters_mut(&mut self) -> Option<&mut dyn Parameters> {
+                Some(self)
+            }
+        }
+
+        impl Parameters for Gain {
+            fn params(&self) -> Vec<ParamInfo> {
+                vec![ParamInfo::new("gain").with_range(0.0, 2.0)]
+            }
+
+            fn param(&self, name: &str) -> Option<f32> {
+                match name {
+                    "gain" => Some(self.0),
+                    _ => None,
+                }
+            }
+
+            fn set_param(&mut self, _name: &str, value: f32) {
+                self.0 = value;
+            }
+        }
+
+        graphity!(
+            Graph<i32>;
+            Recorder = {Recorder, RecorderConsumer, RecorderProducer},
+            Gain = {Gain, GainConsumer, GainProducer},
+        );
+
+        let mut graph = Graph::new();
+        let recorder = graph.add_node(Recorder::default());
+        let gain = graph.add_node(Gain::default());
+
+        assert_eq!(graph.params(gain).unwrap().len(), 1);
+        assert!(graph.params(recorder).unwrap().is_empty());
+
+        graph.set_param(gain, "gain", 1.5).unwrap();
+        assert_eq!(graph.get_param(gain, "gain"), Ok(1.5));
+        assert_eq!(graph.set_param(gain, "gain", 3.0), Err(Error::OutOfRange));
+        assert_eq!(
+            graph.set_param(gain, "cutoff", 1.0),
+            Err(Error::UnknownParameter)
+        );
+        assert_eq!(
+            graph.get_param(recorder, "gain"),
+            Err(Error::UnknownParameter)
+        );
+
+        graph.remove_node(gain);
+        assert_eq!(graph.get_param(gain, "gain"), Err(Error::StaleIndex));
+    }
+
+    //
+    //  [Rec]    __
+    //      \   /  |
+    //       [+]   V
+    //      /   \__|
+    //    [1]
+    //
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_and_deserialize_graph() {
+        use graphity::node::Node;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Default, Serialize, Deserialize)]
+        pub struct Generator(i32);
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+        pub enum GeneratorConsumer {}
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+        pub struct GeneratorProducer;
+
+        impl Node<i32> for Generator {
+            type Consumer = GeneratorConsumer;
+            type Producer = GeneratorProducer;
+
+            fn read(&self, _producer: Self::Producer) -> i32 {
+                self.0
+            }
+        }
+
+        #[derive(Default, Serialize, Deserialize)]
+        pub struct Sum {
+            inputs: [i32; 2],
+            output: i32,
+        }
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+        pub enum SumConsumer {
+            In1,
+            In2,
+        }
+
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+        pub struct SumProducer;
+
+        impl Node<i32> for Sum {
+            type Consumer = SumConsumer;
+            type Producer = SumProducer;
+
+            fn tick(&mut self) {
+                self.output = self.inputs[0] + self.inputs[1];
+            }
+
+            fn read(&self, _producer: Self::Producer) -> i32 {
+                self.output
+            }
+
+            fn write(&mut self, consumer: Self::Consumer, input: i32) {
+                match consumer {
+                    SumConsumer::In1 => self.inputs[0] = input,
+                    SumConsumer::In2 => self.inputs[1] = input,
+                }
+            }
+        }
+
+        graphity!(
+            #[derive(Serialize, Deserialize)]
+            Graph<i32>;
+            Generator = {Generator, GeneratorConsumer, GeneratorProducer},
+            Sum = {Sum, SumConsumer, SumProducer},
+        );
+
+        let mut graph = Graph::new();
+        let removed = graph.add_node(Generator(0));
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        graph.remove_node(removed);
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
+        let last = graph.add_node(Generator(3));
+        graph.remove_node(last);
+        graph.tick();
+        graph.tick();
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let mut loaded: Graph = serde_json::from_str(&json).unwrap();
+
+        assert!(loaded.node(&removed).is_none());
+        assert!(loaded.has_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1)
+        ));
+        assert!(loaded.is_feedback_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2)));
+        assert_eq!(loaded.node(&sum).unwrap().read(SumProducer), 2);
+
+        graph.tick();
+        loaded.tick();
+        assert_eq!(
+            loaded.node(&sum).unwrap().read(SumProducer),
+            graph.node(&sum).unwrap().read(SumProducer)
+        );
+        assert_eq!(
+            serde_json::to_string(&loaded).unwrap(),
+            serde_json::to_string(&graph).unwrap()
+        );
+
+        let other = loaded.add_node(Generator(2));
+        assert!(other.index() > last.index());
+
+        let mut duplicate_node: serde_json::Value = serde_json::to_value(&graph).unwrap();
+        let node = duplicate_node["nodes"][0].clone();
+        duplicate_node["nodes"].as_array_mut().unwrap().push(node);
+        assert!(serde_json::from_value::<Graph>(duplicate_node).is_err());
+
+        let mut misplaced_delay: serde_json::Value = serde_json::to_value(&graph).unwrap();
+        misplaced_delay["delayed_data"][0][0] = misplaced_delay["edges"][0][0].clone();
+        misplaced_delay["delayed_data"][0][1] = misplaced_delay["edges"][0][1].clone();
+        assert!(serde_json::from_value::<Graph>(misplaced_delay).is_err());
+    }
 }
@@ -0,0 +1,112 @@
+//! Journal of topology changes, allowing them to be undone and redone.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::node::DisconnectPolicy;
+use crate::transform::Transform;
+
+/// Edit of the graph topology, stored in the journal as the inverse of the
+/// performed change. Applying an edit yields its own inverse.
+pub enum Edit<N, NI, PI, CI, T> {
+    RemoveNode(NI),
+    RestoreNode {
+        node_index: NI,
+        node: N,
+        priority: i32,
+        edges: Vec<EdgeRecord<PI, CI, T>>,
+        order_constraints: Vec<(NI, NI)>,
+        normals: Vec<NormalRecord<PI, CI, T>>,
+        disconnect_policies: Vec<(CI, DisconnectPolicy<T>)>,
+    },
+    RemoveEdge(PI, CI),
+    RestoreEdge(EdgeRecord<PI, CI, T>),
+    RemoveOrderConstraint(NI, NI),
+    RestoreOrderConstraint(NI, NI),
+    RemoveNormal(CI),
+    RestoreNormal(NormalRecord<PI, CI, T>),
+    /// Edits recorded as a single change. They are applied in reverse order.
+    Batch(Vec<Edit<N, NI, PI, CI, T>>),
+}
+
+/// Everything needed to bring back a removed edge.
+pub struct EdgeRecord<PI, CI, T> {
+    pub producer: PI,
+    pub consumer: CI,
+    pub preferred: bool,
+    pub transform: Option<Box<dyn Transform<T>>>,
+}
+
+/// Everything needed to bring back a normalled producer.
+pub struct NormalRecord<PI, CI, T> {
+    pub consumer: CI,
+    pub producer: PI,
+    pub transform: Option<Box<dyn Transform<T>>>,
+}
+
+pub struct History<E> {
+    undo: Vec<E>,
+    redo: Vec<E>,
+}
+
+impl<E> History<E> {
+    pub fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Record the inverse of a new change. Changes undone so far can no
+    /// longer be redone.
+    pub fn record(&mut self, edit: E) {
+        self.undo.push(edit);
+        self.redo.clear();
+    }
+
+    pub fn pop_undo(&mut self) -> Option<E> {
+        self.undo.pop()
+    }
+
+    pub fn push_undo(&mut self, edit: E) {
+        self.undo.push(edit);
+    }
+
+    pub fn pop_redo(&mut self) -> Option<E> {
+        self.redo.pop()
+    }
+
+    pub fn push_redo(&mut self, edit: E) {
+        self.redo.push(edit);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_clears_redo() {
+        let mut history = History::new();
+        history.record(1);
+        history.record(2);
+
+        let edit = history.pop_undo().unwrap();
+        history.push_redo(edit);
+        assert!(history.can_redo());
+
+        history.record(3);
+        assert!(!history.can_redo());
+        assert_eq!(history.pop_undo(), Some(3));
+        assert_eq!(history.pop_undo(), Some(1));
+        assert!(!history.can_undo());
+    }
+}
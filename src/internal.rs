@@ -1,4 +1,6 @@
+use crate::error::Error;
 use crate::feedback::{FeedbackSink, FeedbackSinkProducer, FeedbackSource, FeedbackSourceConsumer};
+use crate::io::{InputNode, InputNodeProducer, OutputNode, OutputNodeConsumer};
 use crate::node::{
     CommonConsumerIndex, CommonProducerIndex, ConsumerIndex, Node, NodeClass, NodeIndex,
     NodeWrapper, ProducerIndex,
@@ -7,22 +9,28 @@ use crate::node::{
 pub enum InternalNode<T> {
     FeedbackSource(FeedbackSource<T>),
     FeedbackSink(FeedbackSink<T>),
+    Input(InputNode<T>),
+    Output(OutputNode<T>),
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum InternalNodeClass {
     FeedbackSource,
     FeedbackSink,
+    Input,
+    Output,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum InternalConsumer {
     FeedbackSource(FeedbackSourceConsumer),
+    Output(OutputNodeConsumer),
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum InternalProducer {
     FeedbackSink(FeedbackSinkProducer),
+    Input(InputNodeProducer),
 }
 
 impl<T> NodeClass for InternalNode<T> {
@@ -32,6 +40,8 @@ impl<T> NodeClass for InternalNode<T> {
         match self {
             Self::FeedbackSource(_) => Self::Class::FeedbackSource,
             Self::FeedbackSink(_) => Self::Class::FeedbackSink,
+            Self::Input(_) => Self::Class::Input,
+            Self::Output(_) => Self::Class::Output,
         }
     }
 }
@@ -44,15 +54,26 @@ where
     type Consumer = InternalConsumer;
     type Producer = InternalProducer;
 
+    // The value of an input is owned by the host, so it is kept.
+    fn reset(&mut self) {
+        match self {
+            Self::FeedbackSource(feedback_source) => feedback_source.reset(),
+            Self::FeedbackSink(feedback_sink) => feedback_sink.reset(),
+            Self::Input(_) => (),
+            Self::Output(output) => output.reset(),
+        }
+    }
+
     fn read<IntoP>(&self, producer: IntoP) -> T
     where
         IntoP: Into<Self::Producer>,
     {
-        match self {
-            Self::FeedbackSink(feedback_sink) => match producer.into() {
-                Self::Producer::FeedbackSink(producer) => feedback_sink.read(producer),
-            },
-            Self::FeedbackSource(_) => unreachable!("Feedback source does not offer any producers"),
+        match (self, producer.into()) {
+            (Self::FeedbackSink(feedback_sink), Self::Producer::FeedbackSink(producer)) => {
+                feedback_sink.read(producer)
+            }
+            (Self::Input(input), Self::Producer::Input(producer)) => input.read(producer),
+            _ => unreachable!("The node does not offer such producer"),
         }
     }
 
@@ -60,11 +81,14 @@ where
     where
         IntoC: Into<Self::Consumer>,
     {
-        match self {
-            Self::FeedbackSource(feedback_source) => match consumer.into() {
-                Self::Consumer::FeedbackSource(consumer) => feedback_source.write(consumer, input),
-            },
-            Self::FeedbackSink(_) => unreachable!("Feedback sink does not offer any consumers"),
+        match (self, consumer.into()) {
+            (Self::FeedbackSource(feedback_source), Self::Consumer::FeedbackSource(consumer)) => {
+                feedback_source.write(consumer, input)
+            }
+            (Self::Output(output), Self::Consumer::Output(consumer)) => {
+                output.write(consumer, input)
+            }
+            _ => unreachable!("The node does not offer such consumer"),
         }
     }
 }
@@ -89,29 +113,35 @@ impl NodeIndex for InternalNodeIndex {
         Self { class, index }
     }
 
-    fn consumer<IntoC>(&self, consumer: IntoC) -> InternalConsumerIndex
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn try_consumer<IntoC>(&self, consumer: IntoC) -> Result<InternalConsumerIndex, Error>
     where
         IntoC: Into<Self::Consumer>,
     {
         let consumer = consumer.into();
-        match self.class {
-            Self::Class::FeedbackSource => match consumer {
-                Self::Consumer::FeedbackSource(_) => InternalConsumerIndex::new(*self, consumer),
-            },
-            Self::Class::FeedbackSink => panic!("Feedback sink does not offer any consumers"),
+        match (self.class, consumer) {
+            (Self::Class::FeedbackSource, Self::Consumer::FeedbackSource(_))
+            | (Self::Class::Output, Self::Consumer::Output(_)) => {
+                Ok(InternalConsumerIndex::new(*self, consumer))
+            }
+            _ => Err(Error::InvalidPort),
         }
     }
 
-    fn producer<IntoP>(&self, producer: IntoP) -> InternalProducerIndex
+    fn try_producer<IntoP>(&self, producer: IntoP) -> Result<InternalProducerIndex, Error>
     where
         IntoP: Into<Self::Producer>,
     {
         let producer = producer.into();
-        match self.class {
-            Self::Class::FeedbackSink => match producer {
-                Self::Producer::FeedbackSink(_) => InternalProducerIndex::new(*self, producer),
-            },
-            Self::Class::FeedbackSource => panic!("Feedback source does not offer any producers"),
+        match (self.class, producer) {
+            (Self::Class::FeedbackSink, Self::Producer::FeedbackSink(_))
+            | (Self::Class::Input, Self::Producer::Input(_)) => {
+                Ok(InternalProducerIndex::new(*self, producer))
+            }
+            _ => Err(Error::InvalidPort),
         }
     }
 }
@@ -0,0 +1,102 @@
+use crate::node::Node;
+
+pub struct InputNode<T> {
+    value: T,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum InputNodeConsumer {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct InputNodeProducer;
+
+impl<T> InputNode<T>
+where
+    T: Default,
+{
+    pub fn new() -> Self {
+        Self {
+            value: T::default(),
+        }
+    }
+
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+    }
+}
+
+impl<T> Node<T> for InputNode<T>
+where
+    T: Default + Clone,
+{
+    type Consumer = InputNodeConsumer;
+    type Producer = InputNodeProducer;
+
+    fn read(&self, _producer: Self::Producer) -> T {
+        self.value.clone()
+    }
+}
+
+pub struct OutputNode<T> {
+    value: T,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct OutputNodeConsumer;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum OutputNodeProducer {}
+
+impl<T> OutputNode<T>
+where
+    T: Default + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            value: T::default(),
+        }
+    }
+
+    pub fn get(&self) -> T {
+        self.value.clone()
+    }
+}
+
+impl<T> Node<T> for OutputNode<T>
+where
+    T: Default,
+{
+    type Consumer = OutputNodeConsumer;
+    type Producer = OutputNodeProducer;
+
+    fn reset(&mut self) {
+        self.value = T::default();
+    }
+
+    fn write(&mut self, _consumer: Self::Consumer, input: T) {
+        self.value = input;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hold_input_value() {
+        let mut input = InputNode::new();
+        input.set(10);
+        input.tick();
+        assert_eq!(input.read(InputNodeProducer), 10);
+        input.tick();
+        assert_eq!(input.read(InputNodeProducer), 10);
+    }
+
+    #[test]
+    fn hold_output_value() {
+        let mut output = OutputNode::new();
+        output.write(OutputNodeConsumer, [10, 20]);
+        output.tick();
+        assert_eq!(output.get(), [10, 20]);
+    }
+}
@@ -118,7 +118,9 @@
 //! trait](trait.Node.html). You can see that this is where we link previously
 //! defined consumers and producers. Note that the node is implemented for type
 //! `i32`. That is the payload type that will be used for data flowing between
-//! nodes. All consumers and producers within a graph must work with this type:
+//! nodes. All consumers and producers within a graph must work with this type.
+//! Ports working with data of different types can share a payload generated
+//! through the [`payload`](macro.payload.html) macro:
 //!
 //! ```ignore
 //! impl Node<i32> for Sum {
@@ -298,15 +300,30 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+pub mod diff;
 pub mod error;
 mod feedback;
 mod graph;
 mod graphity;
+mod history;
 mod internal;
+mod io;
 pub mod node;
+pub mod param;
+pub mod patch;
+pub mod payload;
+pub mod registry;
 pub mod signal;
 mod sort;
+pub mod transform;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::vec::Vec;
+}
 
 pub use error::Error;
 pub use node::{Node, NodeIndex, NodeWrapper};
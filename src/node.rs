@@ -1,8 +1,16 @@
 //! A set of traits covering registered nodes, consumers, producers and their
 //! indices.
 
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::{self, TypeId};
+use core::fmt;
 use core::hash::Hash;
 
+use crate::error::Error;
+use crate::param::Parameters;
+
 #[doc(hidden)]
 pub trait ExternalNodeWrapper<T: Default + Copy>: NodeWrapper<Payload = T> {}
 
@@ -128,6 +136,15 @@ pub trait Node<T: Default> {
     /// their implementation.
     fn tick(&mut self) {}
 
+    /// Clear the internal state of the node, e.g. memory of a filter or the
+    /// stage of an envelope, as if it was just created.
+    ///
+    /// This is called from
+    /// [`SignalGraph::reset`](../signal/struct.SignalGraph.html#method.reset),
+    /// for example when the transport stops. Default implementation does
+    /// nothing, allowing users to ommit it from their implementation.
+    fn reset(&mut self) {}
+
     /// Read data from the given producer of the node.
     ///
     /// Default implementation returns the default value of carried payload,
@@ -143,6 +160,490 @@ pub trait Node<T: Default> {
     /// their implementation.
     #[allow(unused_variables)]
     fn write(&mut self, consumer: Self::Consumer, input: T) {}
+
+    /// Type of data accepted by the given consumer.
+    ///
+    /// A graph may carry data of multiple types, e.g. audio and gates, through
+    /// a payload generated by the [`payload`](../macro.payload.html) macro.
+    /// Each port then declares the type it works with, as listed in the
+    /// payload. The graph refuses to connect ports of different types, unless
+    /// there is a conversion registered between them.
+    ///
+    /// Default implementation returns `None`, meaning that the consumer accepts
+    /// data of any type.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn consumer_type(_consumer: Self::Consumer) -> Option<PayloadType> {
+    ///     Some(PayloadType::of::<f32>())
+    /// }
+    /// ```
+    #[allow(unused_variables)]
+    fn consumer_type(consumer: Self::Consumer) -> Option<PayloadType>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Type of data offered by the given producer.
+    ///
+    /// See [`consumer_type`](#method.consumer_type) to learn more.
+    ///
+    /// Default implementation returns `None`, meaning that the producer can be
+    /// connected to a consumer of any type.
+    #[allow(unused_variables)]
+    fn producer_type(producer: Self::Producer) -> Option<PayloadType>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// List all consumers offered by the node, together with their
+    /// description.
+    ///
+    /// This is meant for introspection, e.g. by a user interface presenting
+    /// available ports. It is not used while passing data through the graph.
+    ///
+    /// Default implementation returns an empty list, allowing users to ommit it
+    /// from their implementation.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn consumers() -> Vec<PortInfo<Self::Consumer>> {
+    ///     vec![
+    ///         PortInfo::new(SumConsumer::In1, "in1").with_description("First summand"),
+    ///         PortInfo::new(SumConsumer::In2, "in2").with_description("Second summand"),
+    ///     ]
+    /// }
+    /// ```
+    fn consumers() -> Vec<PortInfo<Self::Consumer>>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
+
+    /// List all producers offered by the node, together with their
+    /// description.
+    ///
+    /// See [`consumers`](#method.consumers) to learn more.
+    fn producers() -> Vec<PortInfo<Self::Producer>>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
+
+    /// Construct the node from a list of numeric arguments, such as those
+    /// given to the node in a [patch](../patch/index.html).
+    ///
+    /// This allows to create nodes by the name of their class, see
+    /// [`Registry`](../registry/struct.Registry.html). Default implementation
+    /// returns `None`, meaning that the node cannot be constructed this way.
+    /// Constructors generated by the [`graphity`](../macro.graphity.html)
+    /// macro still construct nodes implementing `Default` through it when no
+    /// arguments are given.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn from_arguments(arguments: &[f32]) -> Option<Self> {
+    ///     match arguments {
+    ///         [] => Some(Self::default()),
+    ///         [frequency] => Some(Self::new(*frequency)),
+    ///         _ => None,
+    ///     }
+    /// }
+    /// ```
+    #[allow(unused_variables)]
+    fn from_arguments(arguments: &[f32]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// List arguments which would construct a node equal to this one through
+    /// [`from_arguments`](#method.from_arguments).
+    ///
+    /// Default implementation returns an empty list.
+    fn arguments(&self) -> Vec<f32> {
+        Vec::new()
+    }
+
+    /// Check whether this instance of the node currently offers the given
+    /// consumer.
+    ///
+    /// Nodes with a variable number of ports, such as a mixer, can expose them
+    /// as an indexed family, e.g. `Input(usize)`. The graph refuses to connect
+    /// edges to consumers which are not offered. After the number of ports
+    /// changes, call
+    /// [`SignalGraph::refresh_ports`](../signal/struct.SignalGraph.html#method.refresh_ports)
+    /// to drop edges of ports which are no longer available.
+    ///
+    /// Default implementation returns `true`, meaning that all the consumers
+    /// are always available.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn offers_consumer(&self, consumer: Self::Consumer) -> bool {
+    ///     match consumer {
+    ///         MixerConsumer::Input(i) => i < self.inputs.len(),
+    ///     }
+    /// }
+    /// ```
+    #[allow(unused_variables)]
+    fn offers_consumer(&self, consumer: Self::Consumer) -> bool {
+        true
+    }
+
+    /// Check whether this instance of the node currently offers the given
+    /// producer.
+    ///
+    /// See [`offers_consumer`](#method.offers_consumer) to learn more.
+    #[allow(unused_variables)]
+    fn offers_producer(&self, producer: Self::Producer) -> bool {
+        true
+    }
+
+    /// Called by the graph after an edge was connected to the given consumer.
+    ///
+    /// This allows nodes to change their behavior based on whether their
+    /// input is patched, e.g. to fall back to an internal value when it is
+    /// not. An edge delayed by a feedback is still considered connected, so
+    /// moving of feedback delays does not trigger this. See
+    /// [`on_delay_change`](#method.on_delay_change) for that.
+    ///
+    /// Default implementation does nothing, allowing users to ommit it from
+    /// their implementation.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn on_connect(&mut self, consumer: Self::Consumer) {
+    ///     match consumer {
+    ///         FilterConsumer::Cutoff => self.cutoff_patched = true,
+    ///         _ => (),
+    ///     }
+    /// }
+    /// ```
+    #[allow(unused_variables)]
+    fn on_connect(&mut self, consumer: Self::Consumer) {}
+
+    /// Called by the graph after the edge connected to the given consumer was
+    /// removed, be it directly or through the removal of the other node. It is
+    /// not called when the edge was dropped because the consumer is no longer
+    /// offered, see [`offers_consumer`](#method.offers_consumer).
+    ///
+    /// See [`on_connect`](#method.on_connect) to learn more.
+    #[allow(unused_variables)]
+    fn on_disconnect(&mut self, consumer: Self::Consumer) {}
+
+    /// Called by the graph after the first edge was connected to the given
+    /// producer.
+    ///
+    /// See [`on_connect`](#method.on_connect) to learn more.
+    #[allow(unused_variables)]
+    fn on_connect_producer(&mut self, producer: Self::Producer) {}
+
+    /// Called by the graph after the last edge connected to the given producer
+    /// was removed. Like [`on_disconnect`](#method.on_disconnect), it is not
+    /// called for producers which are no longer offered.
+    ///
+    /// See [`on_connect`](#method.on_connect) to learn more.
+    #[allow(unused_variables)]
+    fn on_disconnect_producer(&mut self, producer: Self::Producer) {}
+
+    /// Called by the graph after the edge connected to the given consumer
+    /// started or stopped being delayed by a feedback.
+    ///
+    /// The graph moves feedback delays when edges, nodes or order constraints
+    /// change. While `delayed` is `true`, the consumer receives data produced
+    /// during the previous tick.
+    ///
+    /// See [`on_connect`](#method.on_connect) to learn more.
+    #[allow(unused_variables)]
+    fn on_delay_change(&mut self, consumer: Self::Consumer, delayed: bool) {}
+
+    /// Decide what happens with the value of the given consumer after its
+    /// edge is removed.
+    ///
+    /// Default implementation returns
+    /// [`DisconnectPolicy::Keep`](enum.DisconnectPolicy.html), meaning that the
+    /// consumer keeps the last value written to it. The policy can be
+    /// overridden per consumer through
+    /// [`SignalGraph::set_disconnect_policy`](../signal/struct.SignalGraph.html#method.set_disconnect_policy).
+    /// The policy is not applied on consumers which are no longer offered.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn disconnect_policy(&self, consumer: Self::Consumer) -> DisconnectPolicy<f32> {
+    ///     match consumer {
+    ///         OscillatorConsumer::Pitch => DisconnectPolicy::Value(440.0),
+    ///         _ => DisconnectPolicy::Keep,
+    ///     }
+    /// }
+    /// ```
+    #[allow(unused_variables)]
+    fn disconnect_policy(&self, consumer: Self::Consumer) -> DisconnectPolicy<T> {
+        DisconnectPolicy::Keep
+    }
+
+    /// Access named parameters of the node.
+    ///
+    /// Nodes offering parameters should implement
+    /// [`Parameters`](../param/trait.Parameters.html) and return themselves.
+    /// Default implementation returns `None`, meaning that the node has no
+    /// parameters.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// fn parameters(&self) -> Option<&dyn Parameters> {
+    ///     Some(self)
+    /// }
+    /// ```
+    fn parameters(&self) -> Option<&dyn Parameters> {
+        None
+    }
+
+    /// Mutably access named parameters of the node.
+    ///
+    /// See [`parameters`](#method.parameters) to learn more.
+    fn parameters_mut(&mut self) -> Option<&mut dyn Parameters> {
+        None
+    }
+
+    /// List all consumers currently offered by this instance of the node.
+    ///
+    /// Default implementation returns those listed by
+    /// [`consumers`](#method.consumers) which are offered according to
+    /// [`offers_consumer`](#method.offers_consumer). Nodes with indexed
+    /// families of ports should list each member of the family here.
+    fn offered_consumers(&self) -> Vec<PortInfo<Self::Consumer>>
+    where
+        Self: Sized,
+    {
+        Self::consumers()
+            .into_iter()
+            .filter(|port| self.offers_consumer(port.port))
+            .collect()
+    }
+
+    /// List all producers currently offered by this instance of the node.
+    ///
+    /// See [`offered_consumers`](#method.offered_consumers) to learn more.
+    fn offered_producers(&self) -> Vec<PortInfo<Self::Producer>>
+    where
+        Self: Sized,
+    {
+        Self::producers()
+            .into_iter()
+            .filter(|port| self.offers_producer(port.port))
+            .collect()
+    }
+}
+
+/// Description of a consumer or a producer offered by a node.
+///
+/// See [`Node::consumers`](trait.Node.html#method.consumers) to learn more.
+///
+/// # Example
+///
+/// ```
+/// # use graphity::node::PortInfo;
+/// #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+/// pub enum OscillatorConsumer {
+///     Frequency,
+/// }
+///
+/// let port = PortInfo::new(OscillatorConsumer::Frequency, "frequency")
+///     .with_description("Frequency of the generated wave")
+///     .with_range(20.0, 20000.0)
+///     .with_unit("Hz");
+/// ```
+///
+/// Members of an indexed family of ports share the name and are told apart by
+/// their index:
+///
+/// ```
+/// # use graphity::node::PortInfo;
+/// #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+/// pub enum MixerConsumer {
+///     Input(usize),
+/// }
+///
+/// let ports: Vec<_> = (0..4)
+///     .map(|i| PortInfo::new(MixerConsumer::Input(i), "input").with_index(i))
+///     .collect();
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PortInfo<P> {
+    port: P,
+    name: &'static str,
+    index: Option<usize>,
+    description: &'static str,
+    range: Option<(f32, f32)>,
+    unit: Option<&'static str>,
+}
+
+impl<P> PortInfo<P> {
+    /// Describe the given port with a name. The name should be unique among
+    /// consumers or producers of the node.
+    pub fn new(port: P, name: &'static str) -> Self {
+        Self {
+            port,
+            name,
+            index: None,
+            description: "",
+            range: None,
+            unit: None,
+        }
+    }
+
+    /// Mark the port as a member of an indexed family of ports.
+    pub fn with_index(mut self, index: usize) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Add a human readable description of the port.
+    pub fn with_description(mut self, description: &'static str) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Add the range of values expected to pass through the port.
+    pub fn with_range(mut self, min: f32, max: f32) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Add the unit of values passing through the port.
+    pub fn with_unit(mut self, unit: &'static str) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    /// Convert the described port, keeping the rest of the description.
+    pub fn map<Q, F>(self, f: F) -> PortInfo<Q>
+    where
+        F: FnOnce(P) -> Q,
+    {
+        PortInfo {
+            port: f(self.port),
+            name: self.name,
+            index: self.index,
+            description: self.description,
+            range: self.range,
+            unit: self.unit,
+        }
+    }
+
+    /// The described consumer or producer.
+    pub fn port(&self) -> &P {
+        &self.port
+    }
+
+    /// Name of the port, shared by all members of an indexed family.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Index of the port within its family, `None` if it is not a member of
+    /// one.
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    /// Human readable description of the port, empty if not given.
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// Minimum and maximum of values expected to pass through the port.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.range
+    }
+
+    /// Unit of values passing through the port.
+    pub fn unit(&self) -> Option<&'static str> {
+        self.unit
+    }
+
+    /// Name of the port followed by its index in brackets if it is a member
+    /// of an indexed family, e.g. `input[2]`.
+    pub fn label(&self) -> String {
+        port_label(self.name, self.index)
+    }
+}
+
+// Shared by port descriptions and ports referenced in patches.
+pub(crate) fn port_label(name: &str, index: Option<usize>) -> String {
+    match index {
+        Some(index) => format!("{}[{}]", name, index),
+        None => String::from(name),
+    }
+}
+
+/// Value a consumer receives after its edge is removed.
+///
+/// See [`Node::disconnect_policy`](trait.Node.html#method.disconnect_policy)
+/// to learn more.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DisconnectPolicy<T> {
+    /// Keep the last value written to the consumer.
+    Keep,
+    /// Write the default value of the payload to the consumer.
+    Reset,
+    /// Write the given value to the consumer.
+    Value(T),
+}
+
+/// Identifier of a type of data carried by a consumer or a producer.
+///
+/// See [`Node::consumer_type`](trait.Node.html#method.consumer_type) to learn
+/// more.
+///
+/// # Example
+///
+/// ```
+/// # use graphity::node::PayloadType;
+/// let audio = PayloadType::of::<f32>();
+/// let gate = PayloadType::of::<bool>();
+/// assert!(audio != gate);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct PayloadType {
+    id: TypeId,
+    name: &'static str,
+}
+
+impl PayloadType {
+    /// Get the identifier of the given type.
+    pub fn of<T: ?Sized + 'static>() -> Self {
+        Self {
+            id: TypeId::of::<T>(),
+            name: any::type_name::<T>(),
+        }
+    }
+
+    /// Name of the type, meant for diagnostics only.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl fmt::Display for PayloadType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
 }
 
 #[doc(hidden)]
@@ -173,12 +674,14 @@ pub trait NodeWrapper: NodeClass {
 
     fn tick(&mut self) {}
 
+    fn reset(&mut self) {}
+
     /// Read data from the given producer.
     ///
     /// # Panics
     ///
     /// In case the given producer does not belong to this node type, this will
-    /// panic.
+    /// panic. See [`try_read`](#method.try_read) for a fallible alternative.
     #[allow(unused_variables)]
     fn read<IntoP>(&self, producer: IntoP) -> Self::Payload
     where
@@ -192,13 +695,197 @@ pub trait NodeWrapper: NodeClass {
     /// # Panics
     ///
     /// In case the given consumer does not belong to this node type, this will
-    /// panic.
+    /// panic. See [`try_write`](#method.try_write) for a fallible alternative.
     #[allow(unused_variables)]
     fn write<IntoC>(&mut self, consumer: IntoC, _input: Self::Payload)
     where
         IntoC: Into<Self::Consumer>,
     {
     }
+
+    /// Read data from the given producer.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::InvalidPort`](../error/enum.Error.html) if the
+    /// producer does not belong to this node type.
+    fn try_read<IntoP>(&self, producer: IntoP) -> Result<Self::Payload, Error>
+    where
+        IntoP: Into<Self::Producer>,
+    {
+        Ok(self.read(producer))
+    }
+
+    /// Write data into the given consumer.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::InvalidPort`](../error/enum.Error.html) if the
+    /// consumer does not belong to this node type.
+    fn try_write<IntoC>(&mut self, consumer: IntoC, input: Self::Payload) -> Result<(), Error>
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+        self.write(consumer, input);
+        Ok(())
+    }
+
+    /// Type of data accepted by the given consumer.
+    ///
+    /// See [`Node::consumer_type`](trait.Node.html#method.consumer_type) to
+    /// learn more.
+    #[allow(unused_variables)]
+    fn consumer_type<IntoC>(&self, consumer: IntoC) -> Option<PayloadType>
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+        None
+    }
+
+    /// Type of data offered by the given producer.
+    ///
+    /// See [`Node::producer_type`](trait.Node.html#method.producer_type) to
+    /// learn more.
+    #[allow(unused_variables)]
+    fn producer_type<IntoP>(&self, producer: IntoP) -> Option<PayloadType>
+    where
+        IntoP: Into<Self::Producer>,
+    {
+        None
+    }
+
+    /// List all consumers offered by the node, together with their
+    /// description.
+    ///
+    /// See [`Node::offered_consumers`](trait.Node.html#method.offered_consumers)
+    /// to learn more.
+    fn consumers(&self) -> Vec<PortInfo<Self::Consumer>> {
+        Vec::new()
+    }
+
+    /// List all producers offered by the node, together with their
+    /// description.
+    ///
+    /// See [`Node::offered_producers`](trait.Node.html#method.offered_producers)
+    /// to learn more.
+    fn producers(&self) -> Vec<PortInfo<Self::Producer>> {
+        Vec::new()
+    }
+
+    /// Find a consumer offered by the node by its name. Members of indexed
+    /// families are matched by their name and index.
+    ///
+    /// Returns `None` if the node does not offer such consumer.
+    fn find_consumer(&self, name: &str, index: Option<usize>) -> Option<Self::Consumer> {
+        self.consumers()
+            .into_iter()
+            .find(|port| port.name() == name && port.index() == index)
+            .map(|port| *port.port())
+    }
+
+    /// Find a producer offered by the node by its name. Members of indexed
+    /// families are matched by their name and index.
+    ///
+    /// Returns `None` if the node does not offer such producer.
+    fn find_producer(&self, name: &str, index: Option<usize>) -> Option<Self::Producer> {
+        self.producers()
+            .into_iter()
+            .find(|port| port.name() == name && port.index() == index)
+            .map(|port| *port.port())
+    }
+
+    /// List arguments which would construct a node equal to this one.
+    ///
+    /// See [`Node::arguments`](trait.Node.html#method.arguments) to learn more.
+    fn arguments(&self) -> Vec<f32> {
+        Vec::new()
+    }
+
+    /// Check whether the node currently offers the given consumer.
+    ///
+    /// See [`Node::offers_consumer`](trait.Node.html#method.offers_consumer)
+    /// to learn more.
+    #[allow(unused_variables)]
+    fn offers_consumer<IntoC>(&self, consumer: IntoC) -> bool
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+        true
+    }
+
+    /// Check whether the node currently offers the given producer.
+    ///
+    /// See [`Node::offers_producer`](trait.Node.html#method.offers_producer)
+    /// to learn more.
+    #[allow(unused_variables)]
+    fn offers_producer<IntoP>(&self, producer: IntoP) -> bool
+    where
+        IntoP: Into<Self::Producer>,
+    {
+        true
+    }
+
+    /// See [`Node::on_connect`](trait.Node.html#method.on_connect).
+    #[allow(unused_variables)]
+    fn on_connect<IntoC>(&mut self, consumer: IntoC)
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+    }
+
+    /// See [`Node::on_disconnect`](trait.Node.html#method.on_disconnect).
+    #[allow(unused_variables)]
+    fn on_disconnect<IntoC>(&mut self, consumer: IntoC)
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+    }
+
+    /// See
+    /// [`Node::on_connect_producer`](trait.Node.html#method.on_connect_producer).
+    #[allow(unused_variables)]
+    fn on_connect_producer<IntoP>(&mut self, producer: IntoP)
+    where
+        IntoP: Into<Self::Producer>,
+    {
+    }
+
+    /// See
+    /// [`Node::on_disconnect_producer`](trait.Node.html#method.on_disconnect_producer).
+    #[allow(unused_variables)]
+    fn on_disconnect_producer<IntoP>(&mut self, producer: IntoP)
+    where
+        IntoP: Into<Self::Producer>,
+    {
+    }
+
+    /// See [`Node::on_delay_change`](trait.Node.html#method.on_delay_change).
+    #[allow(unused_variables)]
+    fn on_delay_change<IntoC>(&mut self, consumer: IntoC, delayed: bool)
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+    }
+
+    /// See [`Node::parameters`](trait.Node.html#method.parameters).
+    fn parameters(&self) -> Option<&dyn Parameters> {
+        None
+    }
+
+    /// See [`Node::parameters_mut`](trait.Node.html#method.parameters_mut).
+    fn parameters_mut(&mut self) -> Option<&mut dyn Parameters> {
+        None
+    }
+
+    /// See
+    /// [`Node::disconnect_policy`](trait.Node.html#method.disconnect_policy).
+    #[allow(unused_variables)]
+    fn disconnect_policy<IntoC>(&self, consumer: IntoC) -> DisconnectPolicy<Self::Payload>
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+        DisconnectPolicy::Keep
+    }
 }
 
 /// An index serving as a reference to a node stored in a graph.
@@ -232,12 +919,62 @@ pub trait NodeIndex: Copy + Hash + Eq {
 
     #[doc(hidden)]
     fn new(class: Self::Class, index: usize) -> Self;
-    fn consumer<IntoC>(&self, consumer: IntoC) -> Self::ConsumerIndex
+    #[doc(hidden)]
+    fn index(&self) -> usize;
+
+    /// Get an index of the given consumer of the node.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::InvalidPort`](../error/enum.Error.html) if the
+    /// consumer does not belong to this node type.
+    fn try_consumer<IntoC>(&self, consumer: IntoC) -> Result<Self::ConsumerIndex, Error>
     where
         IntoC: Into<Self::Consumer>;
-    fn producer<IntoP>(&self, producer: IntoP) -> Self::ProducerIndex
+
+    /// Get an index of the given producer of the node.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Error::InvalidPort`](../error/enum.Error.html) if the
+    /// producer does not belong to this node type.
+    fn try_producer<IntoP>(&self, producer: IntoP) -> Result<Self::ProducerIndex, Error>
     where
         IntoP: Into<Self::Producer>;
+
+    /// Get an index of the given consumer of the node.
+    ///
+    /// # Panics
+    ///
+    /// In case the given consumer does not belong to this node type, this will
+    /// panic. See [`try_consumer`](#tymethod.try_consumer) for a fallible
+    /// alternative.
+    fn consumer<IntoC>(&self, consumer: IntoC) -> Self::ConsumerIndex
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+        match self.try_consumer(consumer) {
+            Ok(consumer_index) => consumer_index,
+            Err(_) => panic!("Node does not offer such consumer"),
+        }
+    }
+
+    /// Get an index of the given producer of the node.
+    ///
+    /// # Panics
+    ///
+    /// In case the given producer does not belong to this node type, this will
+    /// panic. See [`try_producer`](#tymethod.try_producer) for a fallible
+    /// alternative.
+    fn producer<IntoP>(&self, producer: IntoP) -> Self::ProducerIndex
+    where
+        IntoP: Into<Self::Producer>,
+    {
+        match self.try_producer(producer) {
+            Ok(producer_index) => producer_index,
+            Err(_) => panic!("Node does not offer such producer"),
+        }
+    }
 }
 
 /// An index serving as a unique reference of a consumer of a node registered in
@@ -273,7 +1010,8 @@ pub trait ProducerIndex: Copy + Hash + Eq {
 }
 
 #[doc(hidden)]
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommonConsumerIndex<NI>
 where
     NI: NodeIndex,
@@ -306,7 +1044,8 @@ where
 }
 
 #[doc(hidden)]
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CommonProducerIndex<NI>
 where
     NI: NodeIndex,
@@ -0,0 +1,173 @@
+//! Named parameters of nodes, such as knobs and settings, kept apart from
+//! signal ports.
+//!
+//! Nodes expose their parameters by implementing
+//! [`Parameters`](trait.Parameters.html) and returning themselves from
+//! [`Node::parameters`](../node/trait.Node.html#method.parameters) and
+//! [`Node::parameters_mut`](../node/trait.Node.html#method.parameters_mut).
+//! The graph then allows to access parameters of any node type the same way
+//! through
+//! [`SignalGraph::set_param`](../signal/struct.SignalGraph.html#method.set_param)
+//! and
+//! [`SignalGraph::get_param`](../signal/struct.SignalGraph.html#method.get_param).
+
+use alloc::vec::Vec;
+
+/// Access to named parameters of a node.
+///
+/// # Example
+///
+/// ```
+/// # use graphity::param::{ParamInfo, Parameters};
+/// pub struct Oscillator {
+///     frequency: f32,
+/// }
+///
+/// impl Parameters for Oscillator {
+///     fn params(&self) -> Vec<ParamInfo> {
+///         vec![ParamInfo::new("frequency")
+///             .with_range(20.0, 20000.0)
+///             .with_unit("Hz")]
+///     }
+///
+///     fn param(&self, name: &str) -> Option<f32> {
+///         match name {
+///             "frequency" => Some(self.frequency),
+///             _ => None,
+///         }
+///     }
+///
+///     fn set_param(&mut self, name: &str, value: f32) {
+///         if name == "frequency" {
+///             self.frequency = value;
+///         }
+///     }
+/// }
+/// ```
+pub trait Parameters {
+    /// List all parameters of the node with their description.
+    fn params(&self) -> Vec<ParamInfo>;
+
+    /// Get the description of the parameter.
+    ///
+    /// Returns `None` if the node has no parameter of the given name. Default
+    /// implementation searches through [`params`](#tymethod.params). Nodes
+    /// with many parameters may override it to avoid building the whole list.
+    fn param_info(&self, name: &str) -> Option<ParamInfo> {
+        self.params().into_iter().find(|info| info.name() == name)
+    }
+
+    /// Get the current value of the parameter.
+    ///
+    /// Returns `None` if the node has no parameter of the given name.
+    fn param(&self, name: &str) -> Option<f32>;
+
+    /// Set the value of the parameter. The graph calls this only with names
+    /// listed by [`params`](#tymethod.params) and with values within their
+    /// range.
+    fn set_param(&mut self, name: &str, value: f32);
+}
+
+/// Description of a parameter offered by a node.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ParamInfo {
+    name: &'static str,
+    description: &'static str,
+    range: Option<(f32, f32)>,
+    unit: Option<&'static str>,
+}
+
+impl ParamInfo {
+    /// Describe a parameter with a name. The name should be unique among
+    /// parameters of the node.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            description: "",
+            range: None,
+            unit: None,
+        }
+    }
+
+    /// Add a human readable description of the parameter.
+    pub fn with_description(mut self, description: &'static str) -> Self {
+        self.description = description;
+        self
+    }
+
+    /// Add the range of accepted values.
+    pub fn with_range(mut self, min: f32, max: f32) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Add the unit of the value.
+    pub fn with_unit(mut self, unit: &'static str) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    /// Name of the parameter, used to access it.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Human readable description of the parameter, empty if not given.
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// Minimum and maximum accepted value, `None` if any value is accepted.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.range
+    }
+
+    /// Unit of the value, if given.
+    pub fn unit(&self) -> Option<&'static str> {
+        self.unit
+    }
+
+    /// Check whether the value falls within the range of the parameter.
+    /// Parameters without a range accept any value.
+    pub fn accepts(&self, value: f32) -> bool {
+        match self.range {
+            Some((min, max)) => value >= min && value <= max,
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn accept_values_within_range() {
+        let param = ParamInfo::new("gain").with_range(0.0, 1.0);
+        assert!(param.accepts(0.0));
+        assert!(param.accepts(1.0));
+        assert!(!param.accepts(1.5));
+        assert!(ParamInfo::new("offset").accepts(-100.0));
+    }
+
+    #[test]
+    fn find_param_info_by_name() {
+        struct Gain;
+
+        impl Parameters for Gain {
+            fn params(&self) -> Vec<ParamInfo> {
+                vec![ParamInfo::new("gain"), ParamInfo::new("offset")]
+            }
+
+            fn param(&self, _name: &str) -> Option<f32> {
+                None
+            }
+
+            fn set_param(&mut self, _name: &str, _value: f32) {}
+        }
+
+        assert_eq!(Gain.param_info("offset"), Some(ParamInfo::new("offset")));
+        assert_eq!(Gain.param_info("pan"), None);
+    }
+}
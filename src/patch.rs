@@ -0,0 +1,775 @@
+//! Textual description of a graph.
+//!
+//! A patch lists nodes of the graph and edges between their ports. Each
+//! statement is written on its own line or separated by a semicolon:
+//!
+//! ```text
+//! # Comments start with a hash.
+//! osc1 = Oscillator(440)
+//! filter = Filter(1000, 0.5)
+//! mixer = Mixer(2)
+//! osc1.out -> filter.in; filter.out -> mixer.input[0]
+//! ```
+//!
+//! Nodes are constructed by the name of their class through a
+//! [`NodeFactory`](trait.NodeFactory.html), with the numbers in parentheses
+//! passed as arguments to the constructor. Ports are referenced by the names
+//! they are described with in
+//! [`Node::consumers`](../node/trait.Node.html#method.consumers) and
+//! [`Node::producers`](../node/trait.Node.html#method.producers), members of
+//! indexed families by their name and index in brackets. A node must be
+//! defined before it is referenced by an edge.
+//!
+//! A parsed [`Patch`](struct.Patch.html) can be built into a new
+//! [`SignalGraph`](../signal/struct.SignalGraph.html). The other way around,
+//! a patch can be read from an existing graph and printed through its
+//! `Display` implementation.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use hashbrown::{HashMap, HashSet};
+
+use crate::node::{port_label, ConsumerIndex, NodeIndex, NodeWrapper, ProducerIndex};
+use crate::signal::SignalGraph;
+
+/// Position within the text of a patch. Both the line and the column are
+/// counted from 1.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Error of parsing a patch or building a graph from it, pointing to the
+/// offending position of the text.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PatchError {
+    position: Position,
+    kind: PatchErrorKind,
+}
+
+impl PatchError {
+    fn new(position: Position, kind: PatchErrorKind) -> Self {
+        Self { position, kind }
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn kind(&self) -> &PatchErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.position, self.kind)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PatchError {}
+
+/// Enumeration of all the errors that could happen while parsing a patch or
+/// building a graph from it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PatchErrorKind {
+    /// The character is not allowed in a patch.
+    UnexpectedCharacter(char),
+    /// The statement does not follow the syntax. Carries a description of
+    /// what was expected instead.
+    Expected(&'static str),
+    /// The number cannot be parsed.
+    InvalidNumber,
+    /// Index of a port is not a non-negative integer.
+    InvalidIndex,
+    /// A node of the same name was already defined.
+    DuplicateNode(String),
+    /// There is no class of the given name known to the factory.
+    UnknownClass(String),
+    /// Constructor of the class does not accept given arguments.
+    InvalidArguments(String),
+    /// There is no node of the given name defined.
+    UnknownNode(String),
+    /// The node does not offer a consumer of the given name.
+    UnknownConsumer(String),
+    /// The node does not offer a producer of the given name.
+    UnknownProducer(String),
+    /// The graph refused to add the edge.
+    RefusedEdge(String),
+}
+
+impl fmt::Display for PatchErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedCharacter(c) => write!(f, "unexpected character `{}`", c),
+            Self::Expected(expected) => write!(f, "expected {}", expected),
+            Self::InvalidNumber => write!(f, "invalid number"),
+            Self::InvalidIndex => write!(f, "index must be a non-negative integer"),
+            Self::DuplicateNode(name) => write!(f, "node `{}` is already defined", name),
+            Self::UnknownClass(class) => write!(f, "unknown node class `{}`", class),
+            Self::InvalidArguments(class) => {
+                write!(f, "invalid arguments of node class `{}`", class)
+            }
+            Self::UnknownNode(name) => write!(f, "unknown node `{}`", name),
+            Self::UnknownConsumer(port) => {
+                write!(f, "the node does not offer consumer `{}`", port)
+            }
+            Self::UnknownProducer(port) => {
+                write!(f, "the node does not offer producer `{}`", port)
+            }
+            Self::RefusedEdge(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// Constructors of nodes keyed by the name of their class.
+///
+/// Graphs are built from patches and described back through this trait.
+///
+/// # Example
+///
+/// ```ignore
+/// struct Factory;
+///
+/// impl NodeFactory<__Node> for Factory {
+///     fn contains(&self, class: &str) -> bool {
+///         class == "Generator"
+///     }
+///
+///     fn construct(&self, class: &str, arguments: &[f32]) -> Option<__Node> {
+///         match (class, arguments) {
+///             ("Generator", [value]) => Some(Generator::new(*value as i32).into()),
+///             _ => None,
+///         }
+///     }
+///
+///     fn name(&self, node: &__Node) -> Option<&str> {
+///         match node {
+///             __Node::Generator(_) => Some("Generator"),
+///         }
+///     }
+///
+///     fn arguments(&self, node: &__Node) -> Vec<f32> {
+///         match node {
+///             __Node::Generator(generator) => vec![generator.value() as f32],
+///         }
+///     }
+/// }
+/// ```
+pub trait NodeFactory<N> {
+    /// Check whether a class of the given name is known.
+    fn contains(&self, class: &str) -> bool;
+
+    /// Construct a node of the class with the given name.
+    ///
+    /// Returns `None` if there is no such class or if it does not accept
+    /// given arguments.
+    fn construct(&self, class: &str, arguments: &[f32]) -> Option<N>;
+
+    /// Name of the class of the given node.
+    ///
+    /// Returns `None` if the class is not known.
+    fn name(&self, node: &N) -> Option<&str>;
+
+    /// List arguments which would construct a node equal to the given one.
+    fn arguments(&self, node: &N) -> Vec<f32>;
+}
+
+/// Parsed description of a graph.
+///
+/// # Example
+///
+/// ```ignore
+/// let patch = Patch::parse("gen = Generator(1); rec = Recorder(); gen.out -> rec.in")?;
+/// let (graph, nodes) = patch.build(&factory)?;
+///
+/// let printed = Patch::from_graph(&graph, &factory, &nodes).unwrap().to_string();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Patch {
+    statements: Vec<Statement>,
+}
+
+#[derive(Clone, Debug)]
+enum Statement {
+    Node {
+        name: String,
+        name_position: Position,
+        class: String,
+        class_position: Position,
+        arguments: Vec<f32>,
+    },
+    Edge {
+        producer: PortRef,
+        consumer: PortRef,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct PortRef {
+    node: String,
+    node_position: Position,
+    port: String,
+    index: Option<usize>,
+    port_position: Position,
+}
+
+impl PortRef {
+    fn label(&self) -> String {
+        port_label(&self.port, self.index)
+    }
+}
+
+impl Patch {
+    /// Parse the text of a patch.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error pointing to the first position of the text which
+    /// does not follow the syntax of patches.
+    pub fn parse(text: &str) -> Result<Self, PatchError> {
+        Parser::new(tokenize(text)?).parse()
+    }
+
+    /// Build a new graph containing nodes and edges described by the patch.
+    ///
+    /// Returns the graph together with the indices of its nodes, keyed by
+    /// their names.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error pointing to the statement which references an
+    /// unknown class, node or port, defines a node with a name already taken,
+    /// passes arguments not accepted by the constructor or describes an edge
+    /// refused by the graph.
+    #[allow(clippy::type_complexity)]
+    pub fn build<N, NI, CI, PI>(
+        &self,
+        factory: &impl NodeFactory<N>,
+    ) -> Result<(SignalGraph<N, NI, CI, PI>, HashMap<String, NI>), PatchError>
+    where
+        N: NodeWrapper<Class = NI::Class, Consumer = NI::Consumer, Producer = NI::Producer>,
+        NI: NodeIndex<ConsumerIndex = CI, ProducerIndex = PI>,
+        CI: ConsumerIndex<NodeIndex = NI, Consumer = NI::Consumer> + fmt::Debug,
+        PI: ProducerIndex<NodeIndex = NI, Producer = NI::Producer> + fmt::Debug,
+    {
+        let mut graph = SignalGraph::<N, NI, CI, PI>::new();
+        let mut nodes = HashMap::new();
+
+        for statement in self.statements.iter() {
+            match statement {
+                Statement::Node {
+                    name,
+                    name_position,
+                    class,
+                    class_position,
+                    arguments,
+                } => {
+                    if nodes.contains_key(name) {
+                        return Err(PatchError::new(
+                            *name_position,
+                            PatchErrorKind::DuplicateNode(name.clone()),
+                        ));
+                    }
+                    if !factory.contains(class) {
+                        return Err(PatchError::new(
+                            *class_position,
+                            PatchErrorKind::UnknownClass(class.clone()),
+                        ));
+                    }
+                    let node = factory.construct(class, arguments).ok_or_else(|| {
+                        PatchError::new(
+                            *class_position,
+                            PatchErrorKind::InvalidArguments(class.clone()),
+                        )
+                    })?;
+                    nodes.insert(name.clone(), graph.add_node(node));
+                }
+                Statement::Edge { producer, consumer } => {
+                    let producer_node = find_node(&nodes, producer)?;
+                    let producer_port = graph
+                        .node(&producer_node)
+                        .and_then(|node| node.find_producer(&producer.port, producer.index))
+                        .ok_or_else(|| {
+                            PatchError::new(
+                                producer.port_position,
+                                PatchErrorKind::UnknownProducer(producer.label()),
+                            )
+                        })?;
+
+                    let consumer_node = find_node(&nodes, consumer)?;
+                    let consumer_port = graph
+                        .node(&consumer_node)
+                        .and_then(|node| node.find_consumer(&consumer.port, consumer.index))
+                        .ok_or_else(|| {
+                            PatchError::new(
+                                consumer.port_position,
+                                PatchErrorKind::UnknownConsumer(consumer.label()),
+                            )
+                        })?;
+
+                    graph
+                        .add_edge(
+                            producer_node.producer(producer_port),
+                            consumer_node.consumer(consumer_port),
+                        )
+                        .map_err(|error| {
+                            PatchError::new(
+                                producer.node_position,
+                                PatchErrorKind::RefusedEdge(error.to_string()),
+                            )
+                        })?;
+                }
+            }
+        }
+
+        Ok((graph, nodes))
+    }
+
+    /// Describe nodes and edges of an existing graph.
+    ///
+    /// Nodes are named by the given map, as returned by
+    /// [`build`](#method.build). Nodes missing from it are named after their
+    /// class and index. Arguments of nodes are listed through
+    /// [`NodeFactory::arguments`](trait.NodeFactory.html#tymethod.arguments).
+    ///
+    /// Returns `None` if the class of any of the nodes is not known to the
+    /// factory, if any of the connected ports is not described by its node or
+    /// if any of the names of nodes, classes or ports is not a valid
+    /// identifier, so the patch could not be parsed back.
+    pub fn from_graph<N, NI, CI, PI>(
+        graph: &SignalGraph<N, NI, CI, PI>,
+        factory: &impl NodeFactory<N>,
+        names: &HashMap<String, NI>,
+    ) -> Option<Self>
+    where
+        N: NodeWrapper<Class = NI::Class, Consumer = NI::Consumer, Producer = NI::Producer>,
+        NI: NodeIndex<ConsumerIndex = CI, ProducerIndex = PI>,
+        CI: ConsumerIndex<NodeIndex = NI, Consumer = NI::Consumer>,
+        PI: ProducerIndex<NodeIndex = NI, Producer = NI::Producer>,
+    {
+        if !names.keys().all(|name| is_identifier(name)) {
+            return None;
+        }
+        let mut node_names: HashMap<NI, String> = names
+            .iter()
+            .map(|(name, node_index)| (*node_index, name.clone()))
+            .collect();
+        let mut taken_names: HashSet<String> = names.keys().cloned().collect();
+        let mut statements = Vec::new();
+
+        let mut nodes: Vec<_> = graph.nodes().collect();
+        nodes.sort_by_key(|(node_index, _)| node_index.index());
+        for (node_index, node) in nodes {
+            let class = factory.name(node).filter(|class| is_identifier(class))?;
+            let name = match node_names.get(&node_index) {
+                Some(name) => name.clone(),
+                None => {
+                    let mut name = format!("{}{}", class.to_lowercase(), node_index.index());
+                    while taken_names.contains(&name) {
+                        name.push('_');
+                    }
+                    taken_names.insert(name.clone());
+                    node_names.insert(node_index, name.clone());
+                    name
+                }
+            };
+            statements.push(Statement::Node {
+                name,
+                name_position: Position::default(),
+                class: String::from(class),
+                class_position: Position::default(),
+                arguments: factory.arguments(node),
+            });
+        }
+
+        let mut edges = Vec::new();
+        for (producer, consumer) in graph.edges() {
+            let producer_info = graph
+                .node(&producer.node_index())?
+                .producers()
+                .into_iter()
+                .find(|port| *port.port() == producer.producer())?;
+            let consumer_info = graph
+                .node(&consumer.node_index())?
+                .consumers()
+                .into_iter()
+                .find(|port| *port.port() == consumer.consumer())?;
+            if !is_identifier(producer_info.name()) || !is_identifier(consumer_info.name()) {
+                return None;
+            }
+            edges.push((
+                (producer.node_index().index(), producer_info.label()),
+                (consumer.node_index().index(), consumer_info.label()),
+                Statement::Edge {
+                    producer: PortRef {
+                        node: node_names[&producer.node_index()].clone(),
+                        node_position: Position::default(),
+                        port: String::from(producer_info.name()),
+                        index: producer_info.index(),
+                        port_position: Position::default(),
+                    },
+                    consumer: PortRef {
+                        node: node_names[&consumer.node_index()].clone(),
+                        node_position: Position::default(),
+                        port: String::from(consumer_info.name()),
+                        index: consumer_info.index(),
+                        port_position: Position::default(),
+                    },
+                },
+            ));
+        }
+        edges.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        statements.extend(edges.into_iter().map(|(_, _, statement)| statement));
+
+        Some(Self { statements })
+    }
+}
+
+// Check whether the text would be read as a single identifier.
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn find_node<NI: Copy>(nodes: &HashMap<String, NI>, port: &PortRef) -> Result<NI, PatchError> {
+    nodes.get(&port.node).copied().ok_or_else(|| {
+        PatchError::new(
+            port.node_position,
+            PatchErrorKind::UnknownNode(port.node.clone()),
+        )
+    })
+}
+
+impl fmt::Display for Patch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for statement in self.statements.iter() {
+            match statement {
+                Statement::Node {
+                    name,
+                    class,
+                    arguments,
+                    ..
+                } => {
+                    write!(f, "{} = {}(", name, class)?;
+                    for (i, argument) in arguments.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{}", argument)?;
+                    }
+                    writeln!(f, ")")?;
+                }
+                Statement::Edge { producer, consumer } => writeln!(
+                    f,
+                    "{}.{} -> {}.{}",
+                    producer.node,
+                    producer.label(),
+                    consumer.node,
+                    consumer.label()
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Identifier(String),
+    Number(f32),
+    Equals,
+    Arrow,
+    Dot,
+    Comma,
+    OpenParen,
+    CloseParen,
+    OpenBracket,
+    CloseBracket,
+    Separator,
+    End,
+}
+
+fn tokenize(text: &str) -> Result<Vec<(Token, Position)>, PatchError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut position = Position { line: 1, column: 1 };
+
+    let mut i = 0;
+    while i < chars.len() {
+        let take_while = |predicate: &dyn Fn(usize, char) -> bool| {
+            chars[i..]
+                .iter()
+                .enumerate()
+                .take_while(|(j, c)| predicate(*j, **c))
+                .count()
+        };
+
+        let (token, length) = match chars[i] {
+            '\n' => {
+                tokens.push((Token::Separator, position));
+                position.line += 1;
+                position.column = 1;
+                i += 1;
+                continue;
+            }
+            ' ' | '\t' | '\r' => (None, 1),
+            '#' => (None, take_while(&|_, c| c != '\n')),
+            ';' => (Some(Token::Separator), 1),
+            '=' => (Some(Token::Equals), 1),
+            '.' if !matches!(chars.get(i + 1), Some(c) if c.is_ascii_digit()) => {
+                (Some(Token::Dot), 1)
+            }
+            ',' => (Some(Token::Comma), 1),
+            '(' => (Some(Token::OpenParen), 1),
+            ')' => (Some(Token::CloseParen), 1),
+            '[' => (Some(Token::OpenBracket), 1),
+            ']' => (Some(Token::CloseBracket), 1),
+            '-' if chars.get(i + 1) == Some(&'>') => (Some(Token::Arrow), 2),
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let length = take_while(&|_, c| c.is_ascii_alphanumeric() || c == '_');
+                let identifier = chars[i..i + length].iter().collect();
+                (Some(Token::Identifier(identifier)), length)
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '.' => {
+                let length = take_while(&|j, c| {
+                    j == 0
+                        || c.is_ascii_digit()
+                        || matches!(c, '.' | 'e' | 'E')
+                        || (matches!(c, '+' | '-') && matches!(chars[i + j - 1], 'e' | 'E'))
+                });
+                let number: String = chars[i..i + length].iter().collect();
+                let number = number
+                    .parse()
+                    .map_err(|_| PatchError::new(position, PatchErrorKind::InvalidNumber))?;
+                (Some(Token::Number(number)), length)
+            }
+            c => {
+                return Err(PatchError::new(
+                    position,
+                    PatchErrorKind::UnexpectedCharacter(c),
+                ))
+            }
+        };
+
+        if let Some(token) = token {
+            tokens.push((token, position));
+        }
+        position.column += length;
+        i += length;
+    }
+
+    tokens.push((Token::End, position));
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, Position)>,
+    cursor: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<(Token, Position)>) -> Self {
+        Self { tokens, cursor: 0 }
+    }
+
+    fn parse(mut self) -> Result<Patch, PatchError> {
+        let mut statements = Vec::new();
+
+        loop {
+            match self.peek() {
+                Token::Separator => {
+                    self.advance();
+                }
+                Token::End => break,
+                _ => {
+                    statements.push(self.statement()?);
+                    match self.peek() {
+                        Token::Separator | Token::End => (),
+                        _ => return Err(self.error("end of the statement")),
+                    }
+                }
+            }
+        }
+
+        Ok(Patch { statements })
+    }
+
+    fn statement(&mut self) -> Result<Statement, PatchError> {
+        let (name, name_position) = self.identifier("name of a node")?;
+        match self.peek() {
+            Token::Equals => {
+                self.advance();
+                self.node(name, name_position)
+            }
+            Token::Dot => {
+                let producer = self.port(name, name_position)?;
+                self.expect(Token::Arrow, "`->`")?;
+                let (node, node_position) = self.identifier("name of a node")?;
+                let consumer = self.port(node, node_position)?;
+                Ok(Statement::Edge { producer, consumer })
+            }
+            _ => Err(self.error("`=` or `.`")),
+        }
+    }
+
+    fn node(&mut self, name: String, name_position: Position) -> Result<Statement, PatchError> {
+        let (class, class_position) = self.identifier("name of a class")?;
+        self.expect(Token::OpenParen, "`(`")?;
+
+        let mut arguments = Vec::new();
+        if *self.peek() == Token::CloseParen {
+            self.advance();
+        } else {
+            loop {
+                match self.peek() {
+                    Token::Number(number) => {
+                        arguments.push(*number);
+                        self.advance();
+                    }
+                    _ => return Err(self.error("a number")),
+                }
+                match self.peek() {
+                    Token::Comma => self.advance(),
+                    Token::CloseParen => {
+                        self.advance();
+                        break;
+                    }
+                    _ => return Err(self.error("`,` or `)`")),
+                }
+            }
+        }
+
+        Ok(Statement::Node {
+            name,
+            name_position,
+            class,
+            class_position,
+            arguments,
+        })
+    }
+
+    fn port(&mut self, node: String, node_position: Position) -> Result<PortRef, PatchError> {
+        self.expect(Token::Dot, "`.`")?;
+        let (port, port_position) = self.identifier("name of a port")?;
+
+        let index = if *self.peek() == Token::OpenBracket {
+            self.advance();
+            let (token, position) = self.tokens[self.cursor].clone();
+            let index = match token {
+                Token::Number(number) if number >= 0.0 && number % 1.0 == 0.0 => number as usize,
+                Token::Number(_) => {
+                    return Err(PatchError::new(position, PatchErrorKind::InvalidIndex))
+                }
+                _ => return Err(self.error("an index")),
+            };
+            self.advance();
+            self.expect(Token::CloseBracket, "`]`")?;
+            Some(index)
+        } else {
+            None
+        };
+
+        Ok(PortRef {
+            node,
+            node_position,
+            port,
+            index,
+            port_position,
+        })
+    }
+
+    fn identifier(&mut self, expected: &'static str) -> Result<(String, Position), PatchError> {
+        match self.tokens[self.cursor].clone() {
+            (Token::Identifier(identifier), position) => {
+                self.advance();
+                Ok((identifier, position))
+            }
+            _ => Err(self.error(expected)),
+        }
+    }
+
+    fn expect(&mut self, token: Token, expected: &'static str) -> Result<(), PatchError> {
+        if *self.peek() == token {
+            self.advance();
+            Ok(())
+        } else {
+            Err(self.error(expected))
+        }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.cursor].0
+    }
+
+    // The last token is always `End`, the cursor never moves past it.
+    fn advance(&mut self) {
+        if self.cursor < self.tokens.len() - 1 {
+            self.cursor += 1;
+        }
+    }
+
+    fn error(&self, expected: &'static str) -> PatchError {
+        PatchError::new(
+            self.tokens[self.cursor].1,
+            PatchErrorKind::Expected(expected),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn print_parsed_patch() {
+        let patch = Patch::parse(
+            "# Comment\nosc1 = Oscillator(440, -0.5, 1e3)\n\nmix = Mixer()  ; osc1.out->mix.input[2]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            patch.to_string(),
+            "osc1 = Oscillator(440, -0.5, 1000)\nmix = Mixer()\nosc1.out -> mix.input[2]\n"
+        );
+    }
+
+    #[test]
+    fn report_position_of_syntax_error() {
+        let error = Patch::parse("osc = Oscillator(440)\nosc.out = mix.in").unwrap_err();
+        assert_eq!(error.position(), Position { line: 2, column: 9 });
+        assert_eq!(*error.kind(), PatchErrorKind::Expected("`->`"));
+
+        let error = Patch::parse("osc = Oscillator(440) mix").unwrap_err();
+        assert_eq!(
+            error.position(),
+            Position {
+                line: 1,
+                column: 23
+            }
+        );
+
+        let error = Patch::parse("osc = Oscillator(4$0)").unwrap_err();
+        assert_eq!(error.to_string(), "1:19: unexpected character `$`");
+
+        let error = Patch::parse("osc.out -> mix.input[1.5]").unwrap_err();
+        assert_eq!(
+            error.position(),
+            Position {
+                line: 1,
+                column: 22
+            }
+        );
+        assert_eq!(*error.kind(), PatchErrorKind::InvalidIndex);
+    }
+}
@@ -0,0 +1,143 @@
+//! Payloads combining data of multiple types.
+//!
+//! All the nodes of a graph pass data of a single payload type. To let
+//! consumers and producers work with data of their own types, e.g. audio
+//! (`f32`), gates (`bool`) and MIDI notes (`u8`), the payload can be an
+//! enumeration with a variant for each of these types. The
+//! [`payload`](../macro.payload.html) macro generates such an enumeration,
+//! together with conversions from and to each of the member types.
+//!
+//! Each port then declares the type it works with through
+//! [`Node::consumer_type`](../node/trait.Node.html#method.consumer_type) and
+//! [`Node::producer_type`](../node/trait.Node.html#method.producer_type). The
+//! graph refuses edges between ports of different types, unless there is a
+//! conversion registered between them, e.g. through
+//! [`SignalGraph::register_typed_conversion`](../signal/struct.SignalGraph.html#method.register_typed_conversion).
+//! Nodes extract their own type from the received payload via `TryFrom`.
+
+use crate::node::PayloadType;
+
+/// Payload carrying data of one of multiple types.
+///
+/// This trait is implemented by enumerations generated through the
+/// [`payload`](../macro.payload.html) macro.
+pub trait Payload {
+    /// Type of the data currently carried by the payload.
+    fn payload_type(&self) -> PayloadType;
+}
+
+/// A macro generating a payload enumeration carrying data of multiple types.
+///
+/// Each variant wraps a single type, all of them must be distinct. The first
+/// variant holding the default value of its type is used as the default
+/// payload. Besides the enumeration, the macro implements
+/// [`Payload`](payload/trait.Payload.html), `From` of each member type into
+/// the payload and `TryFrom` of the payload into each member type, returning
+/// the payload back if it carries a different type.
+///
+/// Attributes placed before the enumeration are applied to it. `Clone`,
+/// `Copy`, `PartialEq` and `Debug` are derived always.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate graphity;
+/// # use core::convert::TryFrom;
+/// # use graphity::node::PayloadType;
+/// # use graphity::payload::Payload;
+/// payload! {
+///     pub enum Signal {
+///         Audio(f32),
+///         Gate(bool),
+///     }
+/// }
+///
+/// # fn main() {
+/// let signal = Signal::from(true);
+/// assert_eq!(signal.payload_type(), PayloadType::of::<bool>());
+/// assert_eq!(bool::try_from(signal), Ok(true));
+/// assert_eq!(f32::try_from(signal), Err(signal));
+/// assert_eq!(Signal::default(), Signal::Audio(0.0));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! payload {
+    ( $( #[$attr:meta] )* $vis:vis enum $name:ident { $first:ident($first_type:ty) $( , $variant:ident($type:ty) )* $(,)? } ) => {
+        $( #[$attr] )*
+        #[derive(Clone, Copy, PartialEq, Debug)]
+        $vis enum $name {
+            $first($first_type),
+            $( $variant($type), )*
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::$first(Default::default())
+            }
+        }
+
+        impl graphity::payload::Payload for $name {
+            fn payload_type(&self) -> graphity::node::PayloadType {
+                match self {
+                    Self::$first(_) => graphity::node::PayloadType::of::<$first_type>(),
+                    $(
+                    Self::$variant(_) => graphity::node::PayloadType::of::<$type>(),
+                    )*
+                }
+            }
+        }
+
+        graphity::payload!(@variant $name, $first, $first_type);
+        $(
+        graphity::payload!(@variant $name, $variant, $type);
+        )*
+    };
+    ( @variant $name:ident, $variant:ident, $type:ty ) => {
+        impl From<$type> for $name {
+            fn from(value: $type) -> Self {
+                Self::$variant(value)
+            }
+        }
+
+        impl core::convert::TryFrom<$name> for $type {
+            type Error = $name;
+
+            #[allow(unreachable_patterns)]
+            fn try_from(payload: $name) -> Result<Self, Self::Error> {
+                match payload {
+                    $name::$variant(value) => Ok(value),
+                    payload => Err(payload),
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+    use graphity::node::PayloadType;
+    use graphity::payload::Payload;
+
+    #[test]
+    fn convert_between_payload_and_member_types() {
+        payload! {
+            enum Signal {
+                Audio(f32),
+                Gate(bool),
+                Note(u8),
+            }
+        }
+
+        assert_eq!(Signal::default(), Signal::Audio(0.0));
+        assert_eq!(Signal::from(60_u8), Signal::Note(60));
+        assert_eq!(u8::try_from(Signal::Note(60)), Ok(60));
+        assert_eq!(u8::try_from(Signal::Gate(true)), Err(Signal::Gate(true)));
+        assert_eq!(Signal::Audio(1.0).payload_type(), PayloadType::of::<f32>());
+        assert_eq!(
+            Signal::Gate(false).payload_type(),
+            PayloadType::of::<bool>()
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Construction of nodes and lookup of their ports by name.
+//!
+//! A [`Registry`](struct.Registry.html) maps names of node classes to their
+//! constructors, so nodes can be created from a description available only at
+//! runtime, such as a [patch](../patch/index.html).
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::node::NodeWrapper;
+use crate::patch::NodeFactory;
+
+type Constructor<N> = Box<dyn Fn(&[f32]) -> Option<N>>;
+
+type Arguments<N> = Box<dyn Fn(&N) -> Vec<f32>>;
+
+struct Entry<N>
+where
+    N: NodeWrapper,
+{
+    class: N::Class,
+    name: &'static str,
+    constructor: Constructor<N>,
+    arguments: Option<Arguments<N>>,
+}
+
+/// Collection of named constructors of nodes.
+///
+/// Each class of nodes is registered under a name together with a
+/// constructor accepting a list of numeric arguments. Registries implement
+/// [`NodeFactory`](../patch/trait.NodeFactory.html), so they can be used to
+/// build graphs from patches.
+///
+/// The [`graphity`](../macro.graphity.html) macro generates a registry of all
+/// the nodes of the graph through `__NodeClass::registry`, constructing them
+/// via [`Node::from_arguments`](../node/trait.Node.html#method.from_arguments).
+/// Registries can be also assembled manually.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut registry = Registry::new();
+/// registry.register(__NodeClass::Generator, "Generator", |arguments| {
+///     match arguments {
+///         [value] => Some(Generator::new(*value as i32)),
+///         _ => None,
+///     }
+/// });
+///
+/// let generator = registry.construct("Generator", &[1.0]).unwrap();
+/// ```
+pub struct Registry<N>
+where
+    N: NodeWrapper,
+{
+    entries: Vec<Entry<N>>,
+}
+
+#[allow(clippy::new_without_default)]
+impl<N> Registry<N>
+where
+    N: NodeWrapper,
+{
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Register a constructor of nodes of the given class under a name.
+    ///
+    /// The constructor should return `None` if it does not accept given
+    /// arguments. Registering the same name or class again replaces the
+    /// previous entry.
+    pub fn register<F, IntoN>(&mut self, class: N::Class, name: &'static str, constructor: F)
+    where
+        F: Fn(&[f32]) -> Option<IntoN> + 'static,
+        IntoN: Into<N>,
+    {
+        self.entries
+            .retain(|entry| entry.class != class && entry.name != name);
+        self.entries.push(Entry {
+            class,
+            name,
+            constructor: Box::new(move |arguments| constructor(arguments).map(Into::into)),
+            arguments: None,
+        });
+    }
+
+    /// Register a function listing arguments which would construct a node
+    /// equal to the given one. It is used when a graph is described as a
+    /// patch. Without it, arguments are listed by
+    /// [`Node::arguments`](../node/trait.Node.html#method.arguments).
+    ///
+    /// This has no effect if the class was not registered.
+    pub fn register_arguments<F>(&mut self, class: N::Class, arguments: F)
+    where
+        F: Fn(&N) -> Vec<f32> + 'static,
+    {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.class == class) {
+            entry.arguments = Some(Box::new(arguments));
+        }
+    }
+
+    /// Check whether a class of the given name is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.entry_by_name(name).is_some()
+    }
+
+    /// Construct a node of the class registered under the given name.
+    ///
+    /// Returns `None` if there is no such class or if its constructor does not
+    /// accept given arguments.
+    pub fn construct(&self, name: &str, arguments: &[f32]) -> Option<N> {
+        (self.entry_by_name(name)?.constructor)(arguments)
+    }
+
+    /// Name under which the class of the given node is registered.
+    pub fn name(&self, node: &N) -> Option<&'static str> {
+        self.entry_by_class(node.class()).map(|entry| entry.name)
+    }
+
+    /// List arguments which would construct a node equal to the given one.
+    ///
+    /// See [`register_arguments`](#method.register_arguments) to learn more.
+    pub fn arguments(&self, node: &N) -> Vec<f32> {
+        match self.entry_by_class(node.class()) {
+            Some(Entry {
+                arguments: Some(arguments),
+                ..
+            }) => arguments(node),
+            _ => node.arguments(),
+        }
+    }
+
+    fn entry_by_name(&self, name: &str) -> Option<&Entry<N>> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    fn entry_by_class(&self, class: N::Class) -> Option<&Entry<N>> {
+        self.entries.iter().find(|entry| entry.class == class)
+    }
+}
+
+// Used by the `graphity` macro to construct nodes through `Default` when
+// their class implements it. Calling `construct_default` on
+// `&&DefaultConstructor::<T>::new()` picks `ViaDefault` if `T: Default` and
+// falls back to `WithoutDefault` otherwise. This only works with concrete
+// types, as they are in the code generated by the macro.
+#[doc(hidden)]
+pub struct DefaultConstructor<T>(PhantomData<T>);
+
+impl<T> DefaultConstructor<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaDefault<T> {
+    fn construct_default(&self) -> Option<T>;
+}
+
+impl<T: Default> ViaDefault<T> for &DefaultConstructor<T> {
+    fn construct_default(&self) -> Option<T> {
+        Some(T::default())
+    }
+}
+
+#[doc(hidden)]
+pub trait WithoutDefault<T> {
+    fn construct_default(&self) -> Option<T>;
+}
+
+impl<T> WithoutDefault<T> for DefaultConstructor<T> {
+    fn construct_default(&self) -> Option<T> {
+        None
+    }
+}
+
+impl<N> NodeFactory<N> for Registry<N>
+where
+    N: NodeWrapper,
+{
+    fn contains(&self, class: &str) -> bool {
+        Registry::contains(self, class)
+    }
+
+    fn construct(&self, class: &str, arguments: &[f32]) -> Option<N> {
+        Registry::construct(self, class, arguments)
+    }
+
+    fn name(&self, node: &N) -> Option<&str> {
+        Registry::name(self, node)
+    }
+
+    fn arguments(&self, node: &N) -> Vec<f32> {
+        Registry::arguments(self, node)
+    }
+}
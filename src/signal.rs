@@ -1,21 +1,37 @@
 //! Signal components wrap around the nodes provided by the user and internal
 //! nodes.
 
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
 use alloc::vec::Vec;
-use core::convert::From;
+use core::cmp::Reverse;
+use core::convert::{From, TryFrom};
+use core::fmt;
 use core::hash::Hash;
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 
+use crate::diff::{Diff, NodeRef, Target};
+use crate::error::Error;
 use crate::feedback::{
     self, FeedbackSink, FeedbackSinkProducer, FeedbackSource, FeedbackSourceConsumer,
 };
-use crate::graph::{self, Graph};
+use crate::graph::Graph;
+use crate::history::{EdgeRecord, Edit, History, NormalRecord};
 use crate::internal::{
     InternalConsumer, InternalConsumerIndex, InternalNode, InternalNodeClass, InternalNodeIndex,
     InternalProducer, InternalProducerIndex,
 };
-use crate::node::{ConsumerIndex, NodeClass, NodeIndex, NodeWrapper, ProducerIndex};
+use crate::io::{InputNode, InputNodeProducer, OutputNode, OutputNodeConsumer};
+use crate::node::{
+    ConsumerIndex, DisconnectPolicy, NodeClass, NodeIndex, NodeWrapper, PayloadType, ProducerIndex,
+};
+use crate::param::ParamInfo;
 use crate::sort;
+use crate::transform::{Slots, Transform};
+
+pub use crate::sort::Cycle;
 
 enum SignalNode<N>
 where
@@ -48,6 +64,13 @@ where
             _ => panic!("SignalNode is not of variant Registered"),
         }
     }
+
+    fn must_into_registered(self) -> N {
+        match self {
+            Self::Registered(node) => node,
+            _ => panic!("SignalNode is not of variant Registered"),
+        }
+    }
 }
 
 impl<N> NodeClass for SignalNode<N>
@@ -79,39 +102,149 @@ where
         }
     }
 
+    fn reset(&mut self) {
+        match self {
+            Self::Registered(node) => node.reset(),
+            Self::Internal(node) => node.reset(),
+        }
+    }
+
     fn read<IntoP>(&self, producer: IntoP) -> N::Payload
+    where
+        IntoP: Into<Self::Producer>,
+    {
+        match self.try_read(producer) {
+            Ok(output) => output,
+            Err(_) => panic!("Node does not offer such producer"),
+        }
+    }
+
+    fn write<IntoC>(&mut self, consumer: IntoC, input: N::Payload)
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+        if self.try_write(consumer, input).is_err() {
+            panic!("Node does not offer such consumer");
+        }
+    }
+
+    fn try_read<IntoP>(&self, producer: IntoP) -> Result<N::Payload, Error>
     where
         IntoP: Into<Self::Producer>,
     {
         let producer = producer.into();
         match self {
             Self::Registered(node) => match producer {
-                Self::Producer::Registered(producer) => node.read(producer),
-                _ => panic!("Node does not offer such producer"),
+                Self::Producer::Registered(producer) => node.try_read(producer),
+                _ => Err(Error::InvalidPort),
             },
             Self::Internal(node) => match producer {
-                Self::Producer::Internal(producer) => node.read(producer),
-                _ => panic!("Node does not offer such producer"),
+                Self::Producer::Internal(producer) => Ok(node.read(producer)),
+                _ => Err(Error::InvalidPort),
             },
         }
     }
 
-    fn write<IntoC>(&mut self, consumer: IntoC, input: N::Payload)
+    fn try_write<IntoC>(&mut self, consumer: IntoC, input: N::Payload) -> Result<(), Error>
     where
         IntoC: Into<Self::Consumer>,
     {
         let consumer = consumer.into();
         match self {
             Self::Registered(node) => match consumer {
-                Self::Consumer::Registered(consumer) => node.write(consumer, input),
-                _ => panic!("Node does not offer such consumer"),
+                Self::Consumer::Registered(consumer) => node.try_write(consumer, input),
+                _ => Err(Error::InvalidPort),
             },
             Self::Internal(node) => match consumer {
-                Self::Consumer::Internal(consumer) => node.write(consumer, input),
-                _ => panic!("Node does not offer such consumer"),
+                Self::Consumer::Internal(consumer) => {
+                    node.write(consumer, input);
+                    Ok(())
+                }
+                _ => Err(Error::InvalidPort),
             },
         }
     }
+
+    fn offers_consumer<IntoC>(&self, consumer: IntoC) -> bool
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+        match (self, consumer.into()) {
+            (Self::Registered(node), Self::Consumer::Registered(consumer)) => {
+                node.offers_consumer(consumer)
+            }
+            (Self::Internal(_), Self::Consumer::Internal(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn offers_producer<IntoP>(&self, producer: IntoP) -> bool
+    where
+        IntoP: Into<Self::Producer>,
+    {
+        match (self, producer.into()) {
+            (Self::Registered(node), Self::Producer::Registered(producer)) => {
+                node.offers_producer(producer)
+            }
+            (Self::Internal(_), Self::Producer::Internal(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn on_connect<IntoC>(&mut self, consumer: IntoC)
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+        if let (Self::Registered(node), Self::Consumer::Registered(consumer)) =
+            (self, consumer.into())
+        {
+            node.on_connect(consumer);
+        }
+    }
+
+    fn on_disconnect<IntoC>(&mut self, consumer: IntoC)
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+        if let (Self::Registered(node), Self::Consumer::Registered(consumer)) =
+            (self, consumer.into())
+        {
+            node.on_disconnect(consumer);
+        }
+    }
+
+    fn on_connect_producer<IntoP>(&mut self, producer: IntoP)
+    where
+        IntoP: Into<Self::Producer>,
+    {
+        if let (Self::Registered(node), Self::Producer::Registered(producer)) =
+            (self, producer.into())
+        {
+            node.on_connect_producer(producer);
+        }
+    }
+
+    fn on_disconnect_producer<IntoP>(&mut self, producer: IntoP)
+    where
+        IntoP: Into<Self::Producer>,
+    {
+        if let (Self::Registered(node), Self::Producer::Registered(producer)) =
+            (self, producer.into())
+        {
+            node.on_disconnect_producer(producer);
+        }
+    }
+
+    fn on_delay_change<IntoC>(&mut self, consumer: IntoC, delayed: bool)
+    where
+        IntoC: Into<Self::Consumer>,
+    {
+        if let (Self::Registered(node), Self::Consumer::Registered(consumer)) =
+            (self, consumer.into())
+        {
+            node.on_delay_change(consumer, delayed);
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -127,6 +260,13 @@ impl<NI> SignalNodeIndex<NI> {
             _ => panic!("SignalNodeIndex is not of variant Registered"),
         }
     }
+
+    fn must_internal(&self) -> &InternalNodeIndex {
+        match self {
+            Self::Internal(node_index) => node_index,
+            _ => panic!("SignalNodeIndex is not of variant Internal"),
+        }
+    }
 }
 
 impl<NI> NodeIndex for SignalNodeIndex<NI>
@@ -146,18 +286,25 @@ where
         }
     }
 
-    fn consumer<IntoC>(&self, consumer: IntoC) -> Self::ConsumerIndex
+    fn index(&self) -> usize {
+        match self {
+            Self::Registered(node_index) => node_index.index(),
+            Self::Internal(node_index) => node_index.index(),
+        }
+    }
+
+    fn try_consumer<IntoC>(&self, consumer: IntoC) -> Result<Self::ConsumerIndex, Error>
     where
         IntoC: Into<Self::Consumer>,
     {
-        Self::ConsumerIndex::new(*self, consumer.into())
+        Self::ConsumerIndex::try_new(*self, consumer.into())
     }
 
-    fn producer<IntoP>(&self, producer: IntoP) -> Self::ProducerIndex
+    fn try_producer<IntoP>(&self, producer: IntoP) -> Result<Self::ProducerIndex, Error>
     where
         IntoP: Into<Self::Producer>,
     {
-        Self::ProducerIndex::new(*self, producer.into())
+        Self::ProducerIndex::try_new(*self, producer.into())
     }
 }
 
@@ -179,30 +326,52 @@ where
     Internal(InternalConsumerIndex),
 }
 
-impl<CI> ConsumerIndex for SignalConsumerIndex<CI>
+impl<CI> SignalConsumerIndex<CI>
 where
     CI: ConsumerIndex,
 {
-    type NodeIndex = SignalNodeIndex<CI::NodeIndex>;
-    type Consumer = SignalConsumer<CI::Consumer>;
-
-    fn new(node_index: Self::NodeIndex, consumer: Self::Consumer) -> Self {
+    fn try_new(
+        node_index: SignalNodeIndex<CI::NodeIndex>,
+        consumer: SignalConsumer<CI::Consumer>,
+    ) -> Result<Self, Error> {
         match node_index {
-            Self::NodeIndex::Registered(node_index) => match consumer {
-                Self::Consumer::Registered(consumer) => {
-                    Self::Registered(CI::new(node_index, consumer))
+            SignalNodeIndex::Registered(node_index) => match consumer {
+                SignalConsumer::Registered(consumer) => {
+                    Ok(Self::Registered(CI::new(node_index, consumer)))
                 }
-                _ => panic!("Node does not offer such consumer"),
+                _ => Err(Error::InvalidPort),
             },
-            Self::NodeIndex::Internal(node_index) => match consumer {
-                Self::Consumer::Internal(consumer) => {
-                    Self::Internal(InternalConsumerIndex::new(node_index, consumer))
+            SignalNodeIndex::Internal(node_index) => match consumer {
+                SignalConsumer::Internal(consumer) => {
+                    Ok(Self::Internal(node_index.try_consumer(consumer)?))
                 }
-                _ => panic!("Node does not offer such consumer"),
+                _ => Err(Error::InvalidPort),
             },
         }
     }
 
+    fn must_registered(&self) -> &CI {
+        match self {
+            Self::Registered(consumer_index) => consumer_index,
+            _ => panic!("SignalConsumerIndex is not of variant Registered"),
+        }
+    }
+}
+
+impl<CI> ConsumerIndex for SignalConsumerIndex<CI>
+where
+    CI: ConsumerIndex,
+{
+    type NodeIndex = SignalNodeIndex<CI::NodeIndex>;
+    type Consumer = SignalConsumer<CI::Consumer>;
+
+    fn new(node_index: Self::NodeIndex, consumer: Self::Consumer) -> Self {
+        match Self::try_new(node_index, consumer) {
+            Ok(consumer_index) => consumer_index,
+            Err(_) => panic!("Node does not offer such consumer"),
+        }
+    }
+
     fn node_index(&self) -> Self::NodeIndex {
         match self {
             Self::Registered(consumer_index) => {
@@ -242,30 +411,52 @@ where
     Internal(InternalProducerIndex),
 }
 
-impl<CI> ProducerIndex for SignalProducerIndex<CI>
+impl<CI> SignalProducerIndex<CI>
 where
     CI: ProducerIndex,
 {
-    type NodeIndex = SignalNodeIndex<CI::NodeIndex>;
-    type Producer = SignalProducer<CI::Producer>;
-
-    fn new(node_index: Self::NodeIndex, producer: Self::Producer) -> Self {
+    fn try_new(
+        node_index: SignalNodeIndex<CI::NodeIndex>,
+        producer: SignalProducer<CI::Producer>,
+    ) -> Result<Self, Error> {
         match node_index {
-            Self::NodeIndex::Registered(node_index) => match producer {
-                Self::Producer::Registered(producer) => {
-                    Self::Registered(CI::new(node_index, producer))
+            SignalNodeIndex::Registered(node_index) => match producer {
+                SignalProducer::Registered(producer) => {
+                    Ok(Self::Registered(CI::new(node_index, producer)))
                 }
-                _ => panic!("Node does not offer such producer"),
+                _ => Err(Error::InvalidPort),
             },
-            Self::NodeIndex::Internal(node_index) => match producer {
-                Self::Producer::Internal(producer) => {
-                    Self::Internal(InternalProducerIndex::new(node_index, producer))
+            SignalNodeIndex::Internal(node_index) => match producer {
+                SignalProducer::Internal(producer) => {
+                    Ok(Self::Internal(node_index.try_producer(producer)?))
                 }
-                _ => panic!("Node does not offer such producer"),
+                _ => Err(Error::InvalidPort),
             },
         }
     }
 
+    fn must_registered(&self) -> &CI {
+        match self {
+            Self::Registered(producer_index) => producer_index,
+            _ => panic!("SignalProducerIndex is not of variant Registered"),
+        }
+    }
+}
+
+impl<CI> ProducerIndex for SignalProducerIndex<CI>
+where
+    CI: ProducerIndex,
+{
+    type NodeIndex = SignalNodeIndex<CI::NodeIndex>;
+    type Producer = SignalProducer<CI::Producer>;
+
+    fn new(node_index: Self::NodeIndex, producer: Self::Producer) -> Self {
+        match Self::try_new(node_index, producer) {
+            Ok(producer_index) => producer_index,
+            Err(_) => panic!("Node does not offer such producer"),
+        }
+    }
+
     fn node_index(&self) -> Self::NodeIndex {
         match self {
             Self::Registered(producer_index) => {
@@ -289,19 +480,318 @@ where
 
 /// Enumeration of all the errors that could happen while adding a new edge to
 /// the graph.
-#[derive(Debug)]
-pub enum AddEdgeError {
+///
+/// Each variant carries the producer and the consumer of the refused edge.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AddEdgeError<PI, CI> {
     /// Each consumer must have at most one producer connected to it. If that is
-    /// not the case, this error will be returned.
+    /// not the case, this error will be returned. `existing` is the producer
+    /// already connected to the consumer.
+    OccupiedConsumer {
+        producer: PI,
+        consumer: CI,
+        existing: PI,
+    },
+    /// The consumer is not currently offered by its node, e.g. it is out of
+    /// range of an indexed family of consumers.
+    UnavailableConsumer { producer: PI, consumer: CI },
+    /// The producer is not currently offered by its node, e.g. it is out of
+    /// range of an indexed family of producers.
+    UnavailableProducer { producer: PI, consumer: CI },
+    /// Types declared by the producer and the consumer differ and there is no
+    /// conversion registered between them.
+    IncompatibleTypes {
+        producer: PI,
+        consumer: CI,
+        producer_type: PayloadType,
+        consumer_type: PayloadType,
+    },
+    /// The node of the producer or the consumer is not present in the graph,
+    /// e.g. it was already removed.
+    NodeNotFound { producer: PI, consumer: CI },
+    /// The consumer is already fed by an input of the graph. See
+    /// [`SignalGraph::connect_input`](struct.SignalGraph.html#method.connect_input).
+    ConsumerFedByInput { producer: PI, consumer: CI },
+}
+
+impl<PI, CI> AddEdgeError<PI, CI>
+where
+    PI: Copy,
+    CI: Copy,
+{
+    /// The reason of the refusal, without the indices of the edge.
+    pub fn kind(&self) -> AddEdgeErrorKind {
+        match self {
+            Self::OccupiedConsumer { .. } => AddEdgeErrorKind::OccupiedConsumer,
+            Self::UnavailableConsumer { .. } => AddEdgeErrorKind::UnavailableConsumer,
+            Self::UnavailableProducer { .. } => AddEdgeErrorKind::UnavailableProducer,
+            Self::IncompatibleTypes { .. } => AddEdgeErrorKind::IncompatibleTypes,
+            Self::NodeNotFound { .. } => AddEdgeErrorKind::NodeNotFound,
+            Self::ConsumerFedByInput { .. } => AddEdgeErrorKind::ConsumerFedByInput,
+        }
+    }
+
+    /// The producer of the refused edge.
+    pub fn producer(&self) -> PI {
+        match *self {
+            Self::OccupiedConsumer { producer, .. }
+            | Self::UnavailableConsumer { producer, .. }
+            | Self::UnavailableProducer { producer, .. }
+            | Self::IncompatibleTypes { producer, .. }
+            | Self::NodeNotFound { producer, .. }
+            | Self::ConsumerFedByInput { producer, .. } => producer,
+        }
+    }
+
+    /// The consumer of the refused edge.
+    pub fn consumer(&self) -> CI {
+        match *self {
+            Self::OccupiedConsumer { consumer, .. }
+            | Self::UnavailableConsumer { consumer, .. }
+            | Self::UnavailableProducer { consumer, .. }
+            | Self::IncompatibleTypes { consumer, .. }
+            | Self::NodeNotFound { consumer, .. }
+            | Self::ConsumerFedByInput { consumer, .. } => consumer,
+        }
+    }
+}
+
+impl<PI, CI> fmt::Display for AddEdgeError<PI, CI>
+where
+    PI: fmt::Debug,
+    CI: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OccupiedConsumer {
+                producer,
+                consumer,
+                existing,
+            } => write!(
+                f,
+                "cannot connect {:?} to {:?}, the consumer is already fed by {:?}",
+                producer, consumer, existing
+            ),
+            Self::UnavailableConsumer { producer, consumer } => write!(
+                f,
+                "cannot connect {:?} to {:?}, the consumer is not offered by its node",
+                producer, consumer
+            ),
+            Self::UnavailableProducer { producer, consumer } => write!(
+                f,
+                "cannot connect {:?} to {:?}, the producer is not offered by its node",
+                producer, consumer
+            ),
+            Self::IncompatibleTypes {
+                producer,
+                consumer,
+                producer_type,
+                consumer_type,
+            } => write!(
+                f,
+                "cannot connect {:?} to {:?}, there is no conversion from {} to {}",
+                producer, consumer, producer_type, consumer_type
+            ),
+            Self::NodeNotFound { producer, consumer } => write!(
+                f,
+                "cannot connect {:?} to {:?}, the node is not present in the graph",
+                producer, consumer
+            ),
+            Self::ConsumerFedByInput { producer, consumer } => write!(
+                f,
+                "cannot connect {:?} to {:?}, the consumer is already fed by an input of the graph",
+                producer, consumer
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<PI, CI> std::error::Error for AddEdgeError<PI, CI>
+where
+    PI: fmt::Debug,
+    CI: fmt::Debug,
+{
+}
+
+/// Reason of a refused edge, as returned by
+/// [`AddEdgeError::kind`](enum.AddEdgeError.html#method.kind). See the
+/// variants of [`AddEdgeError`](enum.AddEdgeError.html) for their meaning.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AddEdgeErrorKind {
     OccupiedConsumer,
+    UnavailableConsumer,
+    UnavailableProducer,
+    IncompatibleTypes,
+    NodeNotFound,
+    ConsumerFedByInput,
+}
+
+impl fmt::Display for AddEdgeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OccupiedConsumer => write!(f, "the consumer is already fed by another producer"),
+            Self::UnavailableConsumer => write!(f, "the consumer is not offered by its node"),
+            Self::UnavailableProducer => write!(f, "the producer is not offered by its node"),
+            Self::IncompatibleTypes => write!(f, "there is no conversion between the types"),
+            Self::NodeNotFound => write!(f, "the node is not present in the graph"),
+            Self::ConsumerFedByInput => {
+                write!(f, "the consumer is already fed by an input of the graph")
+            }
+        }
+    }
+}
+
+/// Change of the graph topology.
+///
+/// Events are sent to observers registered through
+/// [`SignalGraph::add_observer`](struct.SignalGraph.html#method.add_observer).
+/// After
+/// [`SignalGraph::enable_event_queue`](struct.SignalGraph.html#method.enable_event_queue),
+/// they are also queued until taken by
+/// [`SignalGraph::drain_events`](struct.SignalGraph.html#method.drain_events).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event<NI, PI, CI> {
+    /// A node was added to the graph.
+    NodeAdded(NI),
+    /// A node was removed from the graph. Removal of its edges, order
+    /// constraints and normalled producers is reported before.
+    NodeRemoved(NI),
+    /// An edge between given producer and consumer was added.
+    EdgeAdded(PI, CI),
+    /// An edge between given producer and consumer was removed.
+    EdgeRemoved(PI, CI),
+    /// The edge between given producer and consumer started delaying its data
+    /// to break a cycle. This happens when rewiring of the graph moves the
+    /// feedback delay of a cycle to a different edge, or when a newly added
+    /// edge closes a cycle.
+    EdgeDelayed(PI, CI),
+    /// The edge between given producer and consumer passes its data directly
+    /// again.
+    EdgeDirect(PI, CI),
+    /// The first node was constrained to tick before the second one.
+    OrderConstraintAdded(NI, NI),
+    /// The order constraint between given nodes was removed.
+    OrderConstraintRemoved(NI, NI),
+    /// The consumer was normalled to the given producer.
+    NormalAdded(PI, CI),
+    /// The consumer is no longer normalled to the given producer.
+    NormalRemoved(PI, CI),
+    /// The order in which nodes are ticked has changed.
+    OrderRecomputed,
+}
+
+/// Enumeration of all the errors that could happen while adding an order
+/// constraint between two nodes.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum OrderConstraintError<NI> {
+    /// One of the nodes is not present in the graph.
+    NodeNotFound { before: NI, after: NI },
+    /// The constraint would close a cycle of order constraints, which, unlike
+    /// a cycle of edges, cannot be broken by a feedback. `cycle` lists the
+    /// nodes of that cycle and the constraints between them.
+    Cycle {
+        before: NI,
+        after: NI,
+        cycle: Cycle<NI>,
+    },
+    /// The constraint would close a cycle with edges which currently pass
+    /// their data directly, forcing one of them to delay its data.
+    DirectEdgeConflict { before: NI, after: NI },
+}
+
+impl<NI> fmt::Display for OrderConstraintError<NI>
+where
+    NI: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NodeNotFound { before, after } => write!(
+                f,
+                "cannot order {:?} before {:?}, the node is not present in the graph",
+                before, after
+            ),
+            Self::Cycle {
+                before,
+                after,
+                cycle,
+            } => write!(
+                f,
+                "cannot order {:?} before {:?}, it would close a cycle of order constraints {:?}",
+                before,
+                after,
+                cycle.nodes()
+            ),
+            Self::DirectEdgeConflict { before, after } => write!(
+                f,
+                "cannot order {:?} before {:?}, it would turn a direct edge into a feedback",
+                before, after
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<NI> std::error::Error for OrderConstraintError<NI> where NI: fmt::Debug {}
+
+/// A loop in the graph, broken by a feedback edge delaying its data.
+///
+/// See
+/// [`SignalGraph::feedback_loops`](struct.SignalGraph.html#method.feedback_loops)
+/// to learn more.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FeedbackLoop<NI, PI, CI> {
+    nodes: Vec<NI>,
+    edges: Vec<(PI, CI)>,
 }
 
-impl From<graph::AddEdgeError> for AddEdgeError {
-    fn from(_error: graph::AddEdgeError) -> Self {
-        AddEdgeError::OccupiedConsumer
+impl<NI, PI, CI> FeedbackLoop<NI, PI, CI>
+where
+    PI: Copy,
+    CI: Copy,
+{
+    /// Nodes forming the loop in the order of the signal flow, starting with
+    /// the node fed by the feedback edge.
+    pub fn nodes(&self) -> &[NI] {
+        &self.nodes
+    }
+
+    /// Edges forming the loop in the order of the signal flow. The first one
+    /// leaves the first node, the last one is the feedback edge.
+    pub fn edges(&self) -> &[(PI, CI)] {
+        &self.edges
+    }
+
+    /// The edge delaying data to break the loop.
+    pub fn feedback(&self) -> (PI, CI) {
+        *self.edges.last().unwrap()
     }
 }
 
+/// Handle of an external input of the graph.
+///
+/// Inputs are added through
+/// [`SignalGraph::add_input`](struct.SignalGraph.html#method.add_input). They
+/// behave like a producer, passing the value last set through
+/// [`SignalGraph::set_input`](struct.SignalGraph.html#method.set_input) to all
+/// the consumers connected to them.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct GraphInput {
+    node_index: InternalNodeIndex,
+}
+
+/// Handle of an external output of the graph.
+///
+/// Outputs are added through
+/// [`SignalGraph::add_output`](struct.SignalGraph.html#method.add_output). They
+/// behave like a consumer, keeping the value last written by the connected
+/// producer, so it can be read through
+/// [`SignalGraph::output`](struct.SignalGraph.html#method.output).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct GraphOutput {
+    node_index: InternalNodeIndex,
+}
+
 /// A graph structure meant to model signal flow between registered nodes.
 ///
 /// Signal graph can be populated with nodes, then producers and consumers of
@@ -316,6 +806,12 @@ impl From<graph::AddEdgeError> for AddEdgeError {
 /// that data passing through the cycle will be delayed by a single `tick` and
 /// only then fed to the consumer.
 ///
+/// The placement of the delay does not depend on the order in which edges were
+/// added. Edges marked through
+/// [`set_feedback_preference`](#method.set_feedback_preference) are delayed
+/// first. If the cycle does not contain any of them, the edge leading from a
+/// later added node back to an earlier added one (or to itself) is delayed.
+///
 /// This structure is not meant to be used directly, instead, user should use
 /// the [`graphity`](../macro.graphity.html) macro to generate it from given
 /// nodes.
@@ -333,7 +829,47 @@ where
         (SignalProducerIndex<PI>, SignalConsumerIndex<CI>),
         (SignalNodeIndex<NI>, SignalNodeIndex<NI>),
     >,
+    preferred_feedbacks: HashSet<(SignalProducerIndex<PI>, SignalConsumerIndex<CI>)>,
+    events: Option<Vec<Event<NI, PI, CI>>>,
+    observers: Vec<Box<dyn FnMut(&Event<NI, PI, CI>)>>,
+    transforms: Slots<CI, N::Payload>,
+    conversions: Slots<(PayloadType, PayloadType), N::Payload>,
+    edge_conversions: HashMap<CI, (PayloadType, PayloadType)>,
+    history: Option<History<Edit<N, NI, PI, CI, N::Payload>>>,
+    batch: Option<Vec<Edit<N, NI, PI, CI, N::Payload>>>,
+    deferred: bool,
+    tick_priorities: HashMap<NI, i32>,
+    order_constraints: HashSet<(NI, NI)>,
+    inputs: HashMap<GraphInput, (&'static str, Option<PayloadType>)>,
+    outputs: HashMap<GraphOutput, (&'static str, Option<PayloadType>)>,
+    output_conversions: HashMap<GraphOutput, (PayloadType, PayloadType)>,
+    disconnect_policies: HashMap<CI, DisconnectPolicy<N::Payload>>,
+    normals: HashMap<CI, (PI, Option<(PayloadType, PayloadType)>)>,
+    normal_transforms: Slots<CI, N::Payload>,
+    active_normals: Vec<(PI, CI, Option<(PayloadType, PayloadType)>)>,
     sorted_nodes: Vec<SignalNodeIndex<NI>>,
+    tick_edges: Vec<Vec<TickEdge<PI, CI>>>,
+    modified_nodes: HashSet<NI>,
+}
+
+// Edge or normalled producer passing data during `tick`, with its conversion
+// and transformation resolved to slots.
+struct TickEdge<PI, CI>
+where
+    CI: ConsumerIndex,
+    PI: ProducerIndex,
+{
+    producer: SignalProducerIndex<PI>,
+    consumer: SignalConsumerIndex<CI>,
+    conversion: Option<usize>,
+    transform: Option<TickTransform>,
+    offered: bool,
+}
+
+#[derive(Clone, Copy)]
+enum TickTransform {
+    Edge(usize),
+    Normal(usize),
 }
 
 #[allow(clippy::new_without_default)]
@@ -363,7 +899,27 @@ where
         Self {
             graph: Graph::new(),
             feedback_edges: HashMap::new(),
+            preferred_feedbacks: HashSet::new(),
+            events: None,
+            observers: Vec::new(),
+            transforms: Slots::new(),
+            conversions: Slots::new(),
+            edge_conversions: HashMap::new(),
+            history: None,
+            batch: None,
+            deferred: false,
+            tick_priorities: HashMap::new(),
+            order_constraints: HashSet::new(),
+            inputs: HashMap::new(),
+            outputs: HashMap::new(),
+            output_conversions: HashMap::new(),
+            disconnect_policies: HashMap::new(),
+            normals: HashMap::new(),
+            normal_transforms: Slots::new(),
+            active_normals: Vec::new(),
             sorted_nodes: Vec::new(),
+            tick_edges: Vec::new(),
+            modified_nodes: HashSet::new(),
         }
     }
 
@@ -382,7 +938,11 @@ where
     {
         let node = SignalNode::Registered(node.into());
         let index = *self.graph.add_node(node).must_registered();
-        self.update_cache();
+        self.emit(Event::NodeAdded(index));
+        if !self.deferred {
+            self.update_cache();
+        }
+        self.record(Edit::RemoveNode(index));
         index
     }
 
@@ -397,706 +957,4675 @@ where
     /// graph.remove_node(generator);
     /// ```
     pub fn remove_node(&mut self, node_index: NI) {
-        let node_index = SignalNodeIndex::Registered(node_index);
-        self.graph.remove_node(node_index);
-        self.update_cache();
+        if let Some(edit) = self.take_node(node_index) {
+            self.record(edit);
+        }
     }
 
-    /// Access a node stored in the graph.
-    ///
-    /// Returns `None` if the `node_index` references a non-existent node.
-    ///
-    /// # Example
-    ///
-    /// ```ignore
-    /// let node = graph.node(generator);
-    /// ```
-    pub fn node(&self, node_index: &NI) -> Option<&N> {
-        let node_index = SignalNodeIndex::Registered(*node_index);
-        Some(self.graph.node(&node_index)?.must_registered())
+    // Remove the node and return an edit that would bring it back together
+    // with all its edges.
+    fn take_node(&mut self, registered_index: NI) -> Option<Edit<N, NI, PI, CI, N::Payload>> {
+        let node_index = SignalNodeIndex::Registered(registered_index);
+        self.graph.node(&node_index)?;
+
+        let disconnected = self.logical_edges_of(node_index);
+        let mut edges = Vec::new();
+        for (producer, consumer) in self.registered_edges() {
+            if producer.node_index() == node_index || consumer.node_index() == node_index {
+                edges.push(self.take_edge_record(producer, consumer));
+                self.forget_edge(*consumer.must_registered());
+            }
+        }
+
+        let feedback_edges: Vec<_> = self
+            .feedback_edges
+            .keys()
+            .filter(|(producer, consumer)| {
+                producer.node_index() == node_index || consumer.node_index() == node_index
+            })
+            .copied()
+            .collect();
+        for (producer, consumer) in feedback_edges {
+            self.remove_feedback_edge(producer, consumer);
+        }
+        self.preferred_feedbacks.retain(|(producer, consumer)| {
+            producer.node_index() != node_index && consumer.node_index() != node_index
+        });
+
+        self.graph.edges.retain(|(producer, consumer)| {
+            producer.node_index() != node_index && consumer.node_index() != node_index
+        });
+        self.notify_disconnected(&disconnected);
+        let node = self.graph.remove_node(node_index)?.must_into_registered();
+        let order_constraints: Vec<_> = self
+            .order_constraints
+            .iter()
+            .filter(|(before, after)| *before == registered_index || *after == registered_index)
+            .copied()
+            .collect();
+        for constraint in order_constraints.iter() {
+            self.order_constraints.remove(constraint);
+        }
+        for (before, after) in order_constraints.iter() {
+            self.emit(Event::OrderConstraintRemoved(*before, *after));
+        }
+        let priority = self
+            .tick_priorities
+            .remove(&registered_index)
+            .unwrap_or_default();
+        let disconnect_policy_consumers: Vec<_> = self
+            .disconnect_policies
+            .keys()
+            .filter(|consumer| consumer.node_index() == registered_index)
+            .copied()
+            .collect();
+        let disconnect_policies: Vec<_> = disconnect_policy_consumers
+            .into_iter()
+            .filter_map(|consumer| {
+                self.disconnect_policies
+                    .remove(&consumer)
+                    .map(|policy| (consumer, policy))
+            })
+            .collect();
+        self.edge_conversions
+            .retain(|consumer, _| consumer.node_index() != registered_index);
+        let normal_consumers: Vec<_> = self
+            .normals
+            .iter()
+            .filter(|(consumer, (producer, _))| {
+                consumer.node_index() == registered_index
+                    || producer.node_index() == registered_index
+            })
+            .map(|(consumer, _)| *consumer)
+            .collect();
+        let normals: Vec<_> = normal_consumers
+            .into_iter()
+            .filter_map(|consumer| self.take_normal(consumer))
+            .collect();
+        for edge in edges.iter() {
+            self.emit(Event::EdgeRemoved(edge.producer, edge.consumer));
+        }
+        self.emit(Event::NodeRemoved(registered_index));
+        self.refresh();
+
+        Some(Edit::RestoreNode {
+            node_index: registered_index,
+            node,
+            priority,
+            edges,
+            order_constraints,
+            normals,
+            disconnect_policies,
+        })
     }
 
-    /// Mutably access a node stored in the graph.
+    /// Set priority of the node, deciding when it ticks relative to nodes it
+    /// does not depend on.
     ///
-    /// Returns `None` if the `node_index` references a non-existent node.
+    /// Each node always ticks after all the nodes feeding it directly. Among
+    /// nodes independent of each other, the ones with higher priority tick
+    /// first. Nodes of the same priority tick in the order in which they were
+    /// added. The default priority is 0. Does nothing if the `node_index` does
+    /// not match an existing node.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// let mut node = graph.node_mut(generator);
+    /// graph.set_tick_priority(generator, 10);
     /// ```
-    pub fn node_mut(&mut self, node_index: &NI) -> Option<&mut N> {
-        let node_index = SignalNodeIndex::Registered(*node_index);
-        Some(self.graph.node_mut(&node_index)?.must_registered_mut())
+    pub fn set_tick_priority(&mut self, node_index: NI, priority: i32) {
+        if self.node(&node_index).is_none() {
+            return;
+        }
+
+        if priority == 0 {
+            self.tick_priorities.remove(&node_index);
+        } else {
+            self.tick_priorities.insert(node_index, priority);
+        }
+
+        if !self.deferred {
+            self.update_cache();
+        }
     }
 
-    /// Add an edge connecting producer of one node to a consumer of another.
+    /// Get priority of the node set through
+    /// [`set_tick_priority`](#method.set_tick_priority).
+    pub fn tick_priority(&self, node_index: NI) -> i32 {
+        self.tick_priorities
+            .get(&node_index)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Make sure that the node `before` ticks before the node `after`, even
+    /// though there may be no edge connecting them.
+    ///
+    /// This is useful for nodes communicating through side channels. The
+    /// constraint affects the order of nodes as an edge would, but it passes
+    /// no data. Constraints of a node are removed together with the node.
     ///
     /// # Errors
     ///
-    /// Will return an error if the consumer is already connected to a different
-    /// producer.
+    /// Will return an error if any of the nodes is not present in the graph.
+    ///
+    /// Will return an error if the constraint would close a cycle with other
+    /// order constraints.
+    ///
+    /// Will return an error if the constraint would close a cycle with edges
+    /// passing data directly, as one of them would have to be delayed by a
+    /// feedback. Edges added later are delayed instead, when they close a
+    /// cycle with the constraint.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// graph.add_edge(
-    ///     generator.producer(GeneratorProducer),
-    ///     echo.consumer(EchoConsumer),
-    /// ).unwrap();
+    /// graph.add_order_constraint(writer, reader)?;
     /// ```
-    ///
-    /// `generator` and `echo` are indices previously returned by `add_node`.
-    /// `GeneratorProducer` and `EchoConsumer` are types defined by the user and
-    /// bound to their respective nodes.
-    pub fn add_edge(&mut self, producer: PI, consumer: CI) -> Result<(), AddEdgeError> {
-        if self.has_edge(producer, consumer) {
-            return Ok(());
+    pub fn add_order_constraint(
+        &mut self,
+        before: NI,
+        after: NI,
+    ) -> Result<(), OrderConstraintError<NI>> {
+        if self.node(&before).is_none() || self.node(&after).is_none() {
+            return Err(OrderConstraintError::NodeNotFound { before, after });
         }
 
-        let producer = SignalProducerIndex::Registered(producer);
-        let consumer = SignalConsumerIndex::Registered(consumer);
-
-        self.graph.add_edge(producer, consumer)?;
-
-        if self.has_cycles() {
-            self.graph.remove_edge(producer, consumer);
-            self.add_feedback_edge(producer, consumer);
+        let constraints: Vec<_> = self
+            .order_constraints
+            .iter()
+            .copied()
+            .chain(core::iter::once((before, after)))
+            .collect();
+        let nodes = constraints
+            .iter()
+            .flat_map(|(before, after)| [*before, *after]);
+        if let Err(cycle) =
+            sort::topological_sort(nodes, constraints.iter().copied(), |node| node.index())
+        {
+            return Err(OrderConstraintError::Cycle {
+                before,
+                after,
+                cycle,
+            });
         }
 
-        self.update_cache();
+        if self.order_constraints.contains(&(before, after)) {
+            return Ok(());
+        }
+        let delayed_edges = self.find_delayed_edges(constraints.iter().copied());
+        if delayed_edges
+            .iter()
+            .any(|edge| !self.feedback_edges.contains_key(edge))
+        {
+            return Err(OrderConstraintError::DirectEdgeConflict { before, after });
+        }
 
+        self.order_constraints.insert((before, after));
+        self.emit(Event::OrderConstraintAdded(before, after));
+        self.refresh();
+        self.record(Edit::RemoveOrderConstraint(before, after));
         Ok(())
     }
 
-    /// Add an edge connecting producer of one node to a consumer of another.
+    /// Remove a previously added order constraint.
+    pub fn remove_order_constraint(&mut self, before: NI, after: NI) {
+        if self.order_constraints.remove(&(before, after)) {
+            self.emit(Event::OrderConstraintRemoved(before, after));
+            self.refresh();
+            self.record(Edit::RestoreOrderConstraint(before, after));
+        }
+    }
+
+    /// Check whether the node `before` is constrained to tick before the node
+    /// `after`.
+    pub fn has_order_constraint(&self, before: NI, after: NI) -> bool {
+        self.order_constraints.contains(&(before, after))
+    }
+
+    /// Access a node stored in the graph.
     ///
-    /// See [`add_edge`](#method.add_edge) for more info.
+    /// Returns `None` if the `node_index` references a non-existent node.
     ///
-    /// # Panics
+    /// # Example
     ///
-    /// Will panic if the consumer is already connected to a different producer.
+    /// ```ignore
+    /// let node = graph.node(generator);
+    /// ```
+    pub fn node(&self, node_index: &NI) -> Option<&N> {
+        let node_index = SignalNodeIndex::Registered(*node_index);
+        Some(self.graph.node(&node_index)?.must_registered())
+    }
+
+    /// Mutably access a node stored in the graph.
+    ///
+    /// Returns `None` if the `node_index` references a non-existent node.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// graph.must_add_edge(
-    ///     generator.producer(GeneratorProducer),
-    ///     echo.consumer(EchoConsumer),
-    /// );
+    /// let mut node = graph.node_mut(generator);
     /// ```
-    ///
-    /// See [`add_edge`](#method.add_edge) for more info.
-    pub fn must_add_edge(&mut self, producer: PI, consumer: CI) {
-        self.add_edge(producer, consumer).unwrap();
+    pub fn node_mut(&mut self, node_index: &NI) -> Option<&mut N> {
+        // The node may stop offering some of its ports, `tick` has to check
+        // them again.
+        self.modified_nodes.insert(*node_index);
+        let node_index = SignalNodeIndex::Registered(*node_index);
+        Some(self.graph.node_mut(&node_index)?.must_registered_mut())
     }
 
-    fn add_feedback_edge(
-        &mut self,
-        producer: SignalProducerIndex<PI>,
-        consumer: SignalConsumerIndex<CI>,
-    ) {
-        let (source, sink) = feedback::new_feedback_pair::<N::Payload>();
-
-        let source = self.graph.add_node(source);
-        let sink = self.graph.add_node(sink);
+    /// Modify a node stored in the graph and remove all edges attached to
+    /// consumers or producers the node no longer offers afterwards.
+    ///
+    /// This is the way to change the number of ports of a node with an
+    /// indexed family of ports. See
+    /// [`refresh_ports`](#method.refresh_ports) to learn more.
+    ///
+    /// Returns the value returned by `modify`, or `None` if the `node_index`
+    /// references a non-existent node.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.modify_node(mixer, |node| {
+    ///     if let __Node::Mixer(mixer) = node {
+    ///         mixer.set_inputs(2);
+    ///     }
+    /// });
+    /// ```
+    pub fn modify_node<F, R>(&mut self, node_index: NI, modify: F) -> Option<R>
+    where
+        F: FnOnce(&mut N) -> R,
+    {
+        let result = modify(self.node_mut(&node_index)?);
+        self.refresh_ports(node_index);
+        Some(result)
+    }
 
+    /// Iterate over all nodes stored in the graph, together with their
+    /// indices. The order of nodes is not defined.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for (node_index, node) in graph.nodes() {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn nodes(&self) -> impl Iterator<Item = (NI, &N)> + '_ {
         self.graph
-            .must_add_edge(producer, source.consumer(FeedbackSourceConsumer));
-        self.graph
-            .must_add_edge(sink.producer(FeedbackSinkProducer), consumer);
-
-        self.feedback_edges
-            .insert((producer, consumer), (source, sink));
+            .nodes
+            .iter()
+            .filter_map(|(node_index, node)| match (node_index, node) {
+                (SignalNodeIndex::Registered(node_index), SignalNode::Registered(node)) => {
+                    Some((*node_index, node))
+                }
+                _ => None,
+            })
     }
 
-    /// Remove the edge connecting the given producer and consumer.
+    /// Read the current output of the given producer.
     ///
-    /// Does nothing if there is no such edge present.
+    /// # Errors
+    ///
+    /// Will return an error if the node of the producer is not present in the
+    /// graph, either because it was removed or because the index was never
+    /// issued by this graph.
+    ///
+    /// Will return an error if the node does not offer the producer.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// graph.remove_edge(
-    ///     generator.producer(GeneratorProducer),
-    ///     echo.consumer(EchoConsumer),
-    /// );
+    /// let output = graph.try_read(generator.producer(GeneratorProducer))?;
     /// ```
-    pub fn remove_edge(&mut self, producer: PI, consumer: CI) {
-        let producer = SignalProducerIndex::Registered(producer);
-        let consumer = SignalConsumerIndex::Registered(consumer);
-
-        if self.graph.has_edge(producer, consumer) {
-            self.graph.remove_edge(producer, consumer);
-            self.drop_redundant_feedbacks();
-        } else if self.feedback_edges.contains_key(&(producer, consumer)) {
-            self.remove_feedback_edge(producer, consumer);
-        }
-
-        self.update_cache();
+    pub fn try_read(&self, producer: PI) -> Result<N::Payload, Error> {
+        let node_index = SignalNodeIndex::Registered(producer.node_index());
+        let node = self
+            .graph
+            .node(&node_index)
+            .ok_or_else(|| self.graph.missing_node_error(&node_index))?;
+        node.must_registered().try_read(producer.producer())
     }
 
-    fn remove_feedback_edge(
-        &mut self,
-        producer: SignalProducerIndex<PI>,
-        consumer: SignalConsumerIndex<CI>,
-    ) {
-        let (source, sink) = self.feedback_edges.get(&(producer, consumer)).unwrap();
-        self.graph.remove_node(*source);
-        self.graph.remove_node(*sink);
-        self.feedback_edges.remove(&(producer, consumer));
+    /// Write the given input directly into a consumer.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the node of the consumer is not present in the
+    /// graph, either because it was removed or because the index was never
+    /// issued by this graph.
+    ///
+    /// Will return an error if the node does not offer the consumer.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.try_write(echo.consumer(EchoConsumer), 1)?;
+    /// ```
+    pub fn try_write(&mut self, consumer: CI, input: N::Payload) -> Result<(), Error> {
+        let node_index = SignalNodeIndex::Registered(consumer.node_index());
+        let error = self.graph.missing_node_error(&node_index);
+        let node = self.graph.node_mut(&node_index).ok_or(error)?;
+        node.must_registered_mut()
+            .try_write(consumer.consumer(), input)
     }
 
-    fn drop_redundant_feedbacks(&mut self) {
-        let edges = self.feedback_edges.clone();
-
-        for ((producer, consumer), (source, sink)) in edges.iter() {
-            let source_consumer = source.consumer(FeedbackSourceConsumer);
-            let sink_producer = sink.producer(FeedbackSinkProducer);
-
-            self.graph.remove_edge(*producer, source_consumer);
-            self.graph.remove_edge(sink_producer, *consumer);
-            self.graph.must_add_edge(*producer, *consumer);
+    /// List named parameters of the node.
+    ///
+    /// Nodes without parameters return an empty list. See the
+    /// [`param`](../param/index.html) module to learn more.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the node is not present in the graph.
+    pub fn params(&self, node_index: NI) -> Result<Vec<ParamInfo>, Error> {
+        let node = self.find_node(node_index)?;
+        Ok(node
+            .parameters()
+            .map(|parameters| parameters.params())
+            .unwrap_or_default())
+    }
 
-            if self.has_cycles() {
-                self.graph.remove_edge(*producer, *consumer);
-                self.graph.must_add_edge(*producer, source_consumer);
-                self.graph.must_add_edge(sink_producer, *consumer);
-            } else {
-                self.graph.remove_node(*source);
-                self.graph.remove_node(*sink);
-                self.feedback_edges.remove(&(*producer, *consumer));
-            }
-        }
+    /// Get the current value of a named parameter of the node.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the node is not present in the graph or if it
+    /// does not offer a parameter of the given name.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let frequency = graph.get_param(oscillator, "frequency")?;
+    /// ```
+    pub fn get_param(&self, node_index: NI, name: &str) -> Result<f32, Error> {
+        self.find_node(node_index)?
+            .parameters()
+            .and_then(|parameters| parameters.param(name))
+            .ok_or(Error::UnknownParameter)
     }
 
-    /// Check whether the graph contains an edge connecting given producer and consumer.
+    /// Set the value of a named parameter of the node.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the node is not present in the graph or if it
+    /// does not offer a parameter of the given name.
+    ///
+    /// Will return an error if the value is out of the range declared by the
+    /// parameter.
     ///
     /// # Example
     ///
     /// ```ignore
-    /// graph.has_edge(
-    ///     generator.producer(GeneratorProducer),
-    ///     echo.consumer(EchoConsumer),
-    /// );
+    /// graph.set_param(oscillator, "frequency", 440.0)?;
     /// ```
-    pub fn has_edge(&mut self, producer: PI, consumer: CI) -> bool {
-        let producer = SignalProducerIndex::Registered(producer);
-        let consumer = SignalConsumerIndex::Registered(consumer);
+    pub fn set_param(&mut self, node_index: NI, name: &str, value: f32) -> Result<(), Error> {
+        let info = self
+            .find_node(node_index)?
+            .parameters()
+            .and_then(|parameters| parameters.param_info(name))
+            .ok_or(Error::UnknownParameter)?;
+        if !info.accepts(value) {
+            return Err(Error::OutOfRange);
+        }
+        if let Some(parameters) = self
+            .node_mut(&node_index)
+            .and_then(|node| node.parameters_mut())
+        {
+            parameters.set_param(name, value);
+        }
+        Ok(())
+    }
 
-        self.graph.has_edge(producer, consumer)
-            || self.feedback_edges.contains_key(&(producer, consumer))
+    fn find_node(&self, node_index: NI) -> Result<&N, Error> {
+        let signal_index = SignalNodeIndex::Registered(node_index);
+        self.node(&node_index)
+            .ok_or_else(|| self.graph.missing_node_error(&signal_index))
     }
 
-    /// Traverse the whole graph and tick all present nodes, passing data
-    /// through registered edges.
+    /// Add a named external input to the graph.
     ///
-    /// A single tick here performs a single iteration through the whole graph.
-    /// It triggers tick of each node, gathers generated data from registered
-    /// producers, passes the data to connected consumers and continues with the
-    /// transition.
+    /// The input can be connected to consumers of registered nodes through
+    /// [`connect_input`](#method.connect_input). Values set through
+    /// [`set_input`](#method.set_input) are then passed to these consumers on
+    /// each tick. This allows a host to feed the graph without accessing its
+    /// nodes.
+    ///
+    /// Like a producer, the input can declare the type of data it passes, see
+    /// [`Node::producer_type`](../node/trait.Node.html#method.producer_type).
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if there already is an input of the same name.
     ///
     /// # Example
     ///
     /// ```ignore
+    /// let input = graph.add_input("in", None)?;
+    /// graph.connect_input(input, echo.consumer(EchoConsumer))?;
+    /// graph.set_input(input, 1)?;
     /// graph.tick();
-    /// // Echo: 1
-    /// graph.tick();
-    /// // Echo: 1
     /// ```
-    pub fn tick(&mut self) {
-        for node_index in self.sorted_nodes.iter() {
-            self.graph.nodes.get_mut(node_index).unwrap().tick();
-
-            for (source_index, destination_index) in self.graph.edges.iter() {
-                if source_index.node_index() != *node_index {
-                    continue;
-                }
+    pub fn add_input(
+        &mut self,
+        name: &'static str,
+        payload_type: Option<PayloadType>,
+    ) -> Result<GraphInput, Error> {
+        if self.find_input(name).is_some() {
+            return Err(Error::DuplicateName);
+        }
 
-                let source = self.graph.node(&source_index.node_index()).unwrap();
-                let output = source.read(source_index.producer());
-                let destination = self
-                    .graph
-                    .nodes
-                    .get_mut(&destination_index.node_index())
-                    .unwrap();
-                destination.write(destination_index.consumer(), output);
-            }
+        let node_index = self.graph.add_node(InputNode::new());
+        let input = GraphInput {
+            node_index: *node_index.must_internal(),
+        };
+        self.inputs.insert(input, (name, payload_type));
+        if !self.deferred {
+            self.update_cache();
         }
+        Ok(input)
     }
 
-    fn update_cache(&mut self) {
-        self.sorted_nodes = self.topologically_sorted_nodes().unwrap();
+    /// Remove an external input together with all its edges.
+    ///
+    /// Does nothing if the input is not present in the graph.
+    pub fn remove_input(&mut self, input: GraphInput) {
+        if self.inputs.remove(&input).is_some() {
+            self.remove_internal_node(SignalNodeIndex::Internal(input.node_index));
+        }
     }
 
-    fn topologically_sorted_nodes(&self) -> Result<Vec<SignalNodeIndex<NI>>, sort::Cycle> {
-        let nodes = self.graph.nodes.keys().copied();
-        let edges = self
-            .graph
-            .edges
+    /// Find an external input by its name.
+    pub fn find_input(&self, name: &str) -> Option<GraphInput> {
+        self.inputs
             .iter()
-            .map(|(producer, consumer)| (producer.node_index(), consumer.node_index()));
-        sort::topological_sort(nodes, edges)
+            .find(|(_, (input_name, _))| *input_name == name)
+            .map(|(input, _)| *input)
     }
 
-    fn has_cycles(&self) -> bool {
-        self.topologically_sorted_nodes().is_err()
+    /// Connect an external input to a consumer of a registered node.
+    ///
+    /// Connections of inputs and outputs are not recorded in the history and
+    /// they are not reported as events.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the input or the node of the consumer is not
+    /// present in the graph.
+    ///
+    /// Will return an error if the consumer is not currently offered by its
+    /// node, if it is already fed by another producer or input, or if its
+    /// type differs from the type of the input and there is no conversion
+    /// registered between them. See [`add_edge`](#method.add_edge).
+    pub fn connect_input(&mut self, input: GraphInput, consumer: CI) -> Result<(), Error> {
+        let input_index = SignalNodeIndex::Internal(input.node_index);
+        let input_type = match self.inputs.get(&input) {
+            Some((_, input_type)) => *input_type,
+            None => return Err(self.graph.missing_node_error(&input_index)),
+        };
+        let node = self.find_node(consumer.node_index())?;
+        if !node.offers_consumer(consumer.consumer()) {
+            return Err(Error::AddEdgeError(AddEdgeErrorKind::UnavailableConsumer));
+        }
+        let conversion = self.io_conversion(input_type, node.consumer_type(consumer.consumer()))?;
+
+        let producer = SignalProducerIndex::Internal(
+            input
+                .node_index
+                .producer(InternalProducer::Input(InputNodeProducer)),
+        );
+        let registered_consumer = consumer;
+        let consumer = SignalConsumerIndex::Registered(consumer);
+        if self.graph.has_edge(producer, consumer) {
+            return Ok(());
+        }
+        if self.is_occupied(consumer) {
+            return Err(Error::AddEdgeError(AddEdgeErrorKind::OccupiedConsumer));
+        }
+
+        self.graph.must_add_edge(producer, consumer);
+        match conversion {
+            Some(conversion) => self
+                .edge_conversions
+                .insert(registered_consumer, conversion),
+            None => self.edge_conversions.remove(&registered_consumer),
+        };
+        self.notify_connected(producer, consumer);
+        self.refresh();
+
+        Ok(())
     }
-}
 
-impl<N> From<FeedbackSource<N::Payload>> for SignalNode<N>
-where
-    N: NodeWrapper,
-{
-    fn from(feedback_source: FeedbackSource<N::Payload>) -> Self {
-        Self::Internal(InternalNode::FeedbackSource(feedback_source))
+    // Find the conversion needed between an external input or output and a
+    // port of a registered node.
+    fn io_conversion(
+        &self,
+        producer_type: Option<PayloadType>,
+        consumer_type: Option<PayloadType>,
+    ) -> Result<Option<(PayloadType, PayloadType)>, Error> {
+        match (producer_type, consumer_type) {
+            (Some(producer_type), Some(consumer_type)) if producer_type != consumer_type => {
+                if self
+                    .conversions
+                    .contains_key(&(producer_type, consumer_type))
+                {
+                    Ok(Some((producer_type, consumer_type)))
+                } else {
+                    Err(Error::AddEdgeError(AddEdgeErrorKind::IncompatibleTypes))
+                }
+            }
+            _ => Ok(None),
+        }
     }
-}
 
-impl<N> From<FeedbackSink<N::Payload>> for SignalNode<N>
-where
-    N: NodeWrapper,
-{
-    fn from(feedback_sink: FeedbackSink<N::Payload>) -> Self {
-        Self::Internal(InternalNode::FeedbackSink(feedback_sink))
+    /// Remove the edge connecting the given external input and consumer.
+    ///
+    /// Does nothing if there is no such edge present.
+    pub fn disconnect_input(&mut self, input: GraphInput, consumer: CI) {
+        let producer = SignalProducerIndex::Internal(
+            input
+                .node_index
+                .producer(InternalProducer::Input(InputNodeProducer)),
+        );
+        let registered_consumer = consumer;
+        let consumer = SignalConsumerIndex::Registered(consumer);
+        if self.graph.has_edge(producer, consumer) {
+            self.graph.remove_edge(producer, consumer);
+            self.edge_conversions.remove(&registered_consumer);
+            self.notify_disconnected(&[(producer, consumer)]);
+            self.refresh();
+        }
     }
-}
 
-impl<C> From<FeedbackSourceConsumer> for SignalConsumer<C>
-where
-    C: Hash + Copy,
-{
-    fn from(feedback_source: FeedbackSourceConsumer) -> Self {
-        Self::Internal(InternalConsumer::FeedbackSource(feedback_source))
+    /// Set the value of an external input. The value is held and passed to
+    /// connected consumers on every tick until set again.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the input is not present in the graph.
+    pub fn set_input(&mut self, input: GraphInput, value: N::Payload) -> Result<(), Error> {
+        let node_index = SignalNodeIndex::Internal(input.node_index);
+        let error = self.graph.missing_node_error(&node_index);
+        match self.graph.node_mut(&node_index) {
+            Some(SignalNode::Internal(InternalNode::Input(node))) => {
+                node.set(value);
+                Ok(())
+            }
+            _ => Err(error),
+        }
     }
-}
 
-impl<P> From<FeedbackSinkProducer> for SignalProducer<P>
-where
-    P: Hash + Copy,
-{
-    fn from(feedback_sink: FeedbackSinkProducer) -> Self {
-        Self::Internal(InternalProducer::FeedbackSink(feedback_sink))
+    /// Add a named external output to the graph.
+    ///
+    /// A producer of a registered node can be connected to the output through
+    /// [`connect_output`](#method.connect_output). The value it produced
+    /// during the last tick can be then read through
+    /// [`output`](#method.output).
+    ///
+    /// Like a consumer, the output can declare the type of data it accepts,
+    /// see [`Node::consumer_type`](../node/trait.Node.html#method.consumer_type).
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if there already is an output of the same name.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let output = graph.add_output("out", None)?;
+    /// graph.connect_output(generator.producer(GeneratorProducer), output)?;
+    /// graph.tick();
+    /// let value = graph.output(output)?;
+    /// ```
+    pub fn add_output(
+        &mut self,
+        name: &'static str,
+        payload_type: Option<PayloadType>,
+    ) -> Result<GraphOutput, Error> {
+        if self.find_output(name).is_some() {
+            return Err(Error::DuplicateName);
+        }
+
+        let node_index = self.graph.add_node(OutputNode::new());
+        let output = GraphOutput {
+            node_index: *node_index.must_internal(),
+        };
+        self.outputs.insert(output, (name, payload_type));
+        if !self.deferred {
+            self.update_cache();
+        }
+        Ok(output)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::feedback::{self, FeedbackSinkProducer, FeedbackSourceConsumer};
-    use crate::node::{
-        CommonConsumerIndex, CommonProducerIndex, ExternalConsumer, ExternalNodeWrapper,
-        ExternalProducer, Node, NodeWrapper,
-    };
-
-    type Payload = i32;
-
-    struct Generator(Payload);
-
-    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
-    enum GeneratorConsumer {}
-
-    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
-    struct GeneratorProducer;
-
-    impl Node<Payload> for Generator {
-        type Consumer = GeneratorConsumer;
-        type Producer = GeneratorProducer;
-
-        fn read(&self, _producer: Self::Producer) -> Payload {
-            self.0
+    /// Remove an external output together with its edge.
+    ///
+    /// Does nothing if the output is not present in the graph.
+    pub fn remove_output(&mut self, output: GraphOutput) {
+        if self.outputs.remove(&output).is_some() {
+            self.output_conversions.remove(&output);
+            self.remove_internal_node(SignalNodeIndex::Internal(output.node_index));
         }
     }
 
-    impl From<Generator> for TestNode {
-        fn from(generator: Generator) -> Self {
-            TestNode::Generator(generator)
-        }
+    /// Find an external output by its name.
+    pub fn find_output(&self, name: &str) -> Option<GraphOutput> {
+        self.outputs
+            .iter()
+            .find(|(_, (output_name, _))| *output_name == name)
+            .map(|(output, _)| *output)
     }
 
-    impl From<GeneratorProducer> for TestProducer {
-        fn from(generator: GeneratorProducer) -> Self {
-            TestProducer::Generator(generator)
+    /// Connect a producer of a registered node to an external output.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the output or the node of the producer is not
+    /// present in the graph.
+    ///
+    /// Will return an error if the producer is not currently offered by its
+    /// node, if the output is already fed by another producer, or if the type
+    /// of the producer differs from the type of the output and there is no
+    /// conversion registered between them. See
+    /// [`add_edge`](#method.add_edge).
+    pub fn connect_output(&mut self, producer: PI, output: GraphOutput) -> Result<(), Error> {
+        let output_index = SignalNodeIndex::Internal(output.node_index);
+        let output_type = match self.outputs.get(&output) {
+            Some((_, output_type)) => *output_type,
+            None => return Err(self.graph.missing_node_error(&output_index)),
+        };
+        let node = self.find_node(producer.node_index())?;
+        if !node.offers_producer(producer.producer()) {
+            return Err(Error::AddEdgeError(AddEdgeErrorKind::UnavailableProducer));
         }
-    }
-
-    #[derive(Default)]
-    struct Sum {
-        input1: Payload,
-        input2: Payload,
-        output: Payload,
-    }
-
-    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
-    enum SumConsumer {
-        In1,
-        In2,
-    }
-
-    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
-    struct SumProducer;
-
-    impl Node<Payload> for Sum {
-        type Consumer = SumConsumer;
-        type Producer = SumProducer;
+        let conversion =
+            self.io_conversion(node.producer_type(producer.producer()), output_type)?;
 
-        fn tick(&mut self) {
-            self.output = self.input1 + self.input2;
+        let producer = SignalProducerIndex::Registered(producer);
+        let consumer = SignalConsumerIndex::Internal(
+            output
+                .node_index
+                .consumer(InternalConsumer::Output(OutputNodeConsumer)),
+        );
+        if self.graph.has_edge(producer, consumer) {
+            return Ok(());
         }
-
-        fn read(&self, _producer: Self::Producer) -> Payload {
-            self.output
+        if self.is_occupied(consumer) {
+            return Err(Error::AddEdgeError(AddEdgeErrorKind::OccupiedConsumer));
         }
 
-        fn write(&mut self, consumer: Self::Consumer, input: Payload) {
-            match consumer {
-                Self::Consumer::In1 => self.input1 = input,
-                Self::Consumer::In2 => self.input2 = input,
-            }
-        }
+        self.graph.must_add_edge(producer, consumer);
+        match conversion {
+            Some(conversion) => self.output_conversions.insert(output, conversion),
+            None => self.output_conversions.remove(&output),
+        };
+        self.notify_connected(producer, consumer);
+        self.refresh();
+
+        Ok(())
     }
 
-    impl From<Sum> for TestNode {
-        fn from(sum: Sum) -> Self {
-            TestNode::Sum(sum)
+    /// Remove the edge connecting the given producer and external output.
+    ///
+    /// Does nothing if there is no such edge present.
+    pub fn disconnect_output(&mut self, producer: PI, output: GraphOutput) {
+        let producer = SignalProducerIndex::Registered(producer);
+        let consumer = SignalConsumerIndex::Internal(
+            output
+                .node_index
+                .consumer(InternalConsumer::Output(OutputNodeConsumer)),
+        );
+        if self.graph.has_edge(producer, consumer) {
+            self.graph.remove_edge(producer, consumer);
+            self.notify_disconnected(&[(producer, consumer)]);
+            self.refresh();
         }
     }
 
-    impl From<SumConsumer> for TestConsumer {
-        fn from(sum: SumConsumer) -> Self {
-            TestConsumer::Sum(sum)
+    /// Read the value written to an external output during the last tick.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the output is not present in the graph.
+    pub fn output(&self, output: GraphOutput) -> Result<N::Payload, Error> {
+        let node_index = SignalNodeIndex::Internal(output.node_index);
+        match self.graph.node(&node_index) {
+            Some(SignalNode::Internal(InternalNode::Output(node))) => Ok(node.get()),
+            _ => Err(self.graph.missing_node_error(&node_index)),
         }
     }
 
-    impl From<SumProducer> for TestProducer {
-        fn from(sum: SumProducer) -> Self {
-            TestProducer::Sum(sum)
+    /// Add an edge connecting producer of one node to a consumer of another.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the consumer is already connected to a different
+    /// producer.
+    ///
+    /// Will return an error if the node of the producer or the consumer is not
+    /// present in the graph.
+    ///
+    /// Will return an error if the producer or the consumer is not currently
+    /// offered by its node.
+    ///
+    /// Will return an error if the producer and the consumer declare different
+    /// types of data and there is no conversion registered between them. See
+    /// [`register_conversion`](#method.register_conversion) for more info.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.add_edge(
+    ///     generator.producer(GeneratorProducer),
+    ///     echo.consumer(EchoConsumer),
+    /// ).unwrap();
+    /// ```
+    ///
+    /// `generator` and `echo` are indices previously returned by `add_node`.
+    /// `GeneratorProducer` and `EchoConsumer` are types defined by the user and
+    /// bound to their respective nodes.
+    pub fn add_edge(&mut self, producer: PI, consumer: CI) -> Result<(), AddEdgeError<PI, CI>> {
+        if self.has_edge(producer, consumer) {
+            return Ok(());
         }
-    }
 
-    #[derive(Default)]
-    struct Recorder(Payload);
+        self.check_ports(producer, consumer)?;
 
-    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
-    struct RecorderConsumer;
+        if let Some(existing) = self.connected_producer(consumer) {
+            return Err(AddEdgeError::OccupiedConsumer {
+                producer,
+                consumer,
+                existing,
+            });
+        }
+        if self.is_occupied(SignalConsumerIndex::Registered(consumer)) {
+            return Err(AddEdgeError::ConsumerFedByInput { producer, consumer });
+        }
 
-    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
-    struct RecorderProducer;
+        let conversion = self.conversion_between(producer, consumer)?;
 
-    impl Node<Payload> for Recorder {
-        type Consumer = RecorderConsumer;
-        type Producer = RecorderProducer;
+        let edge = (
+            SignalProducerIndex::Registered(producer),
+            SignalConsumerIndex::Registered(consumer),
+        );
+        self.graph.must_add_edge(edge.0, edge.1);
+        self.notify_connected(edge.0, edge.1);
+        match conversion {
+            Some(conversion) => self.edge_conversions.insert(consumer, conversion),
+            None => self.edge_conversions.remove(&consumer),
+        };
+        self.emit(Event::EdgeAdded(producer, consumer));
 
-        fn read(&self, _producer: Self::Producer) -> Payload {
-            self.0
-        }
+        self.refresh();
+        self.record(Edit::RemoveEdge(producer, consumer));
 
-        fn write(&mut self, _consumer: Self::Consumer, input: Payload) {
-            self.0 = input;
-        }
+        Ok(())
     }
 
-    impl From<Recorder> for TestNode {
-        fn from(recorder: Recorder) -> Self {
-            TestNode::Recorder(recorder)
+    // Check that both nodes are present and offer the given ports.
+    fn check_ports(&self, producer: PI, consumer: CI) -> Result<(), AddEdgeError<PI, CI>> {
+        let (producer_node, consumer_node) = match (
+            self.node(&producer.node_index()),
+            self.node(&consumer.node_index()),
+        ) {
+            (Some(producer_node), Some(consumer_node)) => (producer_node, consumer_node),
+            _ => return Err(AddEdgeError::NodeNotFound { producer, consumer }),
+        };
+        if !producer_node.offers_producer(producer.producer()) {
+            return Err(AddEdgeError::UnavailableProducer { producer, consumer });
         }
-    }
-
-    impl From<RecorderConsumer> for TestConsumer {
-        fn from(recorder: RecorderConsumer) -> Self {
-            TestConsumer::Recorder(recorder)
+        if !consumer_node.offers_consumer(consumer.consumer()) {
+            return Err(AddEdgeError::UnavailableConsumer { producer, consumer });
         }
+        Ok(())
     }
 
-    impl From<RecorderProducer> for TestProducer {
-        fn from(recorder: RecorderProducer) -> Self {
-            TestProducer::Recorder(recorder.into())
-        }
+    /// Normal the consumer to the given producer.
+    ///
+    /// Like an unpatched jack of a hardware module, the consumer then receives
+    /// data of the producer whenever there is no edge connected to it. As soon
+    /// as an edge is connected, the normalled producer is suspended. It is
+    /// used again once the edge is removed. Normalled producers do not occupy
+    /// the consumer, they do not trigger
+    /// [`Node::on_connect`](../node/trait.Node.html#method.on_connect) and they
+    /// are not listed among edges of the graph. If the normalled producer
+    /// closes a cycle, its data are delayed by a single tick.
+    ///
+    /// Setting a new normalled producer replaces the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the node of the producer or the consumer is not
+    /// present in the graph, if any of the ports is not offered by its node or
+    /// if their types differ and there is no conversion registered between
+    /// them.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.set_normalled_producer(
+    ///     vca2.consumer(VcaConsumer::Input),
+    ///     vca1.producer(VcaProducer),
+    /// )?;
+    /// ```
+    pub fn set_normalled_producer(
+        &mut self,
+        consumer: CI,
+        producer: PI,
+    ) -> Result<(), AddEdgeError<PI, CI>> {
+        let edit = self.replace_normal(NormalRecord {
+            consumer,
+            producer,
+            transform: None,
+        })?;
+        self.record(edit);
+        Ok(())
     }
 
-    enum TestNode {
-        Generator(Generator),
-        Sum(Sum),
-        Recorder(Recorder),
-    }
+    // Normal the consumer as described by the record, replacing its previous
+    // normalled producer. Returns an edit that would revert this.
+    #[allow(clippy::type_complexity)]
+    fn replace_normal(
+        &mut self,
+        record: NormalRecord<PI, CI, N::Payload>,
+    ) -> Result<Edit<N, NI, PI, CI, N::Payload>, AddEdgeError<PI, CI>> {
+        let NormalRecord {
+            consumer,
+            producer,
+            transform,
+        } = record;
+
+        self.check_ports(producer, consumer)?;
+        let conversion = self.conversion_between(producer, consumer)?;
+        let previous = self.take_normal(consumer);
+        self.normals.insert(consumer, (producer, conversion));
+        if let Some(transform) = transform {
+            self.normal_transforms.insert(consumer, transform);
+        }
+        self.emit(Event::NormalAdded(producer, consumer));
+        if !self.deferred {
+            self.update_cache();
+        }
 
-    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
-    enum TestNodeClass {
-        Generator,
-        Sum,
-        Recorder,
+        Ok(match previous {
+            Some(previous) => Edit::RestoreNormal(previous),
+            None => Edit::RemoveNormal(consumer),
+        })
     }
 
-    impl NodeClass for TestNode {
-        type Class = TestNodeClass;
-
-        fn class(&self) -> Self::Class {
-            match self {
-                Self::Generator(_) => TestNodeClass::Generator,
-                Self::Sum(_) => TestNodeClass::Sum,
-                Self::Recorder(_) => TestNodeClass::Recorder,
+    /// Remove the normalled producer of the consumer.
+    ///
+    /// Does nothing if the consumer is not normalled.
+    pub fn remove_normalled_producer(&mut self, consumer: CI) {
+        if let Some(record) = self.take_normal(consumer) {
+            if !self.deferred {
+                self.update_cache();
             }
+            self.record(Edit::RestoreNormal(record));
         }
     }
 
-    impl NodeWrapper for TestNode {
-        type Payload = Payload;
-        type Consumer = TestConsumer;
-        type Producer = TestProducer;
-
-        fn tick(&mut self) {
-            match self {
-                Self::Generator(generator) => generator.tick(),
-                Self::Sum(sum) => sum.tick(),
-                Self::Recorder(recorder) => recorder.tick(),
-            }
-        }
+    // Remove the normalled producer of the consumer and return a record that
+    // would bring it back together with its transformation.
+    fn take_normal(&mut self, consumer: CI) -> Option<NormalRecord<PI, CI, N::Payload>> {
+        let (producer, _) = self.normals.remove(&consumer)?;
+        let transform = self.normal_transforms.remove(&consumer);
+        self.emit(Event::NormalRemoved(producer, consumer));
+        Some(NormalRecord {
+            consumer,
+            producer,
+            transform,
+        })
+    }
 
-        fn read<IntoP>(&self, producer: IntoP) -> Payload
-        where
-            IntoP: Into<Self::Producer>,
+    /// Attach a transformation to the normalled producer of the consumer.
+    ///
+    /// The transformation will be applied on the data passed from the
+    /// normalled producer, the same way
+    /// [`set_edge_transform`](#method.set_edge_transform) does for edges. It
+    /// replaces any transformation previously attached and it is dropped
+    /// together with the normalled producer.
+    ///
+    /// Does nothing if the consumer is not normalled.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.set_normal_transform(vca2.consumer(VcaConsumer::Input), Scale(0.5));
+    /// ```
+    pub fn set_normal_transform<T>(&mut self, consumer: CI, transform: T)
+    where
+        T: Transform<N::Payload> + 'static,
+    {
+        if self.normals.contains_key(&consumer)
+            && self.normal_transforms.insert(consumer, Box::new(transform))
         {
-            let producer = producer.into();
-            match self {
-                Self::Generator(generator) => match producer {
-                    Self::Producer::Generator(producer) => generator.read(producer),
-                    _ => panic!("Node does not offer such producer"),
-                },
-                Self::Sum(sum) => match producer {
-                    Self::Producer::Sum(producer) => sum.read(producer),
-                    _ => panic!("Node does not offer such producer"),
-                },
-                Self::Recorder(recorder) => match producer {
-                    Self::Producer::Recorder(producer) => recorder.read(producer),
-                    _ => panic!("Node does not offer such producer"),
-                },
-            }
+            self.refresh_tick_edges();
         }
+    }
 
-        fn write<IntoC>(&mut self, consumer: IntoC, input: Payload)
-        where
-            IntoC: Into<Self::Consumer>,
-        {
-            let consumer = consumer.into();
-            match self {
-                Self::Generator(_) => panic!("Node does not offer such consumer"),
-                Self::Sum(sum) => match consumer {
-                    Self::Consumer::Sum(consumer) => sum.write(consumer.into(), input),
-                    _ => panic!("Node does not offer such consumer"),
-                },
-                Self::Recorder(recorder) => match consumer {
-                    Self::Consumer::Recorder(consumer) => recorder.write(consumer.into(), input),
-                    _ => panic!("Node does not offer such consumer"),
-                },
-            }
+    /// Detach transformation from the normalled producer of the consumer,
+    /// letting the data pass unchanged.
+    pub fn remove_normal_transform(&mut self, consumer: CI) {
+        if self.normal_transforms.remove(&consumer).is_some() {
+            self.refresh_tick_edges();
         }
     }
 
-    impl ExternalNodeWrapper<Payload> for TestNode {}
+    /// Check whether the normalled producer of the consumer has a
+    /// transformation attached.
+    pub fn has_normal_transform(&self, consumer: CI) -> bool {
+        self.normal_transforms.contains_key(&consumer)
+    }
 
-    #[derive(PartialEq, Eq, Copy, Clone, Hash)]
-    struct TestNodeIndex {
-        class: TestNodeClass,
-        index: usize,
+    /// Get the producer the consumer is normalled to, if any.
+    pub fn normalled_producer(&self, consumer: CI) -> Option<PI> {
+        self.normals.get(&consumer).map(|(producer, _)| *producer)
     }
 
-    impl NodeIndex for TestNodeIndex {
-        type Class = TestNodeClass;
-        type Consumer = TestConsumer;
-        type ConsumerIndex = TestConsumerIndex;
-        type Producer = TestProducer;
-        type ProducerIndex = TestProducerIndex;
+    // List normalled producers which are currently in use, as their consumers
+    // are not occupied by any edge.
+    #[allow(clippy::type_complexity)]
+    fn find_active_normals(&self) -> Vec<(PI, CI, Option<(PayloadType, PayloadType)>)> {
+        let mut active_normals: Vec<_> = self
+            .normals
+            .iter()
+            .filter(|(consumer, _)| !self.is_occupied(SignalConsumerIndex::Registered(**consumer)))
+            .map(|(consumer, (producer, conversion))| (*producer, *consumer, *conversion))
+            .collect();
+        active_normals.sort_by_key(|(_, consumer, _)| consumer.node_index().index());
+        active_normals
+    }
 
-        fn new(class: TestNodeClass, index: usize) -> Self {
-            Self { class, index }
+    // Find which conversion, if any, is needed to pass data from the producer
+    // to the consumer.
+    fn conversion_between(
+        &self,
+        producer: PI,
+        consumer: CI,
+    ) -> Result<Option<(PayloadType, PayloadType)>, AddEdgeError<PI, CI>> {
+        let producer_type = self
+            .node(&producer.node_index())
+            .and_then(|node| node.producer_type(producer.producer()));
+        let consumer_type = self
+            .node(&consumer.node_index())
+            .and_then(|node| node.consumer_type(consumer.consumer()));
+
+        match (producer_type, consumer_type) {
+            (Some(producer_type), Some(consumer_type)) if producer_type != consumer_type => {
+                if self
+                    .conversions
+                    .contains_key(&(producer_type, consumer_type))
+                {
+                    Ok(Some((producer_type, consumer_type)))
+                } else {
+                    Err(AddEdgeError::IncompatibleTypes {
+                        producer,
+                        consumer,
+                        producer_type,
+                        consumer_type,
+                    })
+                }
+            }
+            _ => Ok(None),
         }
+    }
 
-        fn consumer<IntoC>(&self, consumer: IntoC) -> Self::ConsumerIndex
-        where
-            IntoC: Into<TestConsumer>,
-        {
-            let consumer = consumer.into();
-            match self.class {
-                Self::Class::Generator => panic!("Node does not offer such consumer"),
-                Self::Class::Sum => match consumer {
-                    Self::Consumer::Sum(_) => Self::ConsumerIndex::new(*self, consumer.into()),
-                    _ => panic!("Node does not offer such consumer"),
-                },
-                Self::Class::Recorder => match consumer {
-                    Self::Consumer::Recorder(_) => Self::ConsumerIndex::new(*self, consumer.into()),
-                    _ => panic!("Node does not offer such consumer"),
-                },
+    /// Register a conversion between two types of data declared by consumers
+    /// and producers.
+    ///
+    /// Once registered, edges can connect producers of type `from` to
+    /// consumers of type `to`. The conversion will be applied on all data
+    /// passed through such edges. It replaces any conversion previously
+    /// registered between the same types.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.register_conversion(
+    ///     PayloadType::of::<bool>(),
+    ///     PayloadType::of::<f32>(),
+    ///     |payload| match payload {
+    ///         Payload::Gate(gate) => Payload::Audio(if gate { 1.0 } else { 0.0 }),
+    ///         payload => payload,
+    ///     },
+    /// );
+    /// ```
+    pub fn register_conversion<T>(&mut self, from: PayloadType, to: PayloadType, conversion: T)
+    where
+        T: Transform<N::Payload> + 'static,
+    {
+        self.conversions.insert((from, to), Box::new(conversion));
+    }
+
+    /// Register a conversion between two types carried by a payload generated
+    /// through the [`payload`](../macro.payload.html) macro.
+    ///
+    /// Unlike [`register_conversion`](#method.register_conversion), the
+    /// conversion works directly with the types declared by consumers and
+    /// producers. Payload of a different type than `A` is passed unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.register_typed_conversion(|gate: bool| if gate { 1.0 } else { 0.0 });
+    /// ```
+    pub fn register_typed_conversion<A, B, F>(&mut self, mut conversion: F)
+    where
+        A: TryFrom<N::Payload> + 'static,
+        B: Into<N::Payload> + 'static,
+        F: FnMut(A) -> B + 'static,
+    {
+        self.register_conversion(
+            PayloadType::of::<A>(),
+            PayloadType::of::<B>(),
+            move |payload| match A::try_from(payload) {
+                Ok(value) => conversion(value).into(),
+                Err(_) => payload,
+            },
+        );
+    }
+
+    /// Add an edge connecting producer of one node to a consumer of another.
+    ///
+    /// See [`add_edge`](#method.add_edge) for more info.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the consumer is already connected to a different producer.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.must_add_edge(
+    ///     generator.producer(GeneratorProducer),
+    ///     echo.consumer(EchoConsumer),
+    /// );
+    /// ```
+    ///
+    /// See [`add_edge`](#method.add_edge) for more info.
+    pub fn must_add_edge(&mut self, producer: PI, consumer: CI) {
+        if let Err(error) = self.add_edge(producer, consumer) {
+            panic!("Failed to add an edge: {}", Error::from(error));
+        }
+    }
+
+    fn add_feedback_edge(
+        &mut self,
+        producer: SignalProducerIndex<PI>,
+        consumer: SignalConsumerIndex<CI>,
+    ) {
+        let (source, sink) = feedback::new_feedback_pair::<N::Payload>();
+
+        let source = self.graph.add_node(source);
+        let sink = self.graph.add_node(sink);
+
+        self.graph
+            .must_add_edge(producer, source.consumer(FeedbackSourceConsumer));
+        self.graph
+            .must_add_edge(sink.producer(FeedbackSinkProducer), consumer);
+
+        self.feedback_edges
+            .insert((producer, consumer), (source, sink));
+    }
+
+    /// Remove the edge connecting the given producer and consumer.
+    ///
+    /// Does nothing if there is no such edge present.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.remove_edge(
+    ///     generator.producer(GeneratorProducer),
+    ///     echo.consumer(EchoConsumer),
+    /// );
+    /// ```
+    pub fn remove_edge(&mut self, producer: PI, consumer: CI) {
+        if let Some(edit) = self.take_edge(producer, consumer) {
+            self.record(edit);
+        }
+    }
+
+    // Remove the edge and return an edit that would bring it back.
+    fn take_edge(&mut self, producer: PI, consumer: CI) -> Option<Edit<N, NI, PI, CI, N::Payload>> {
+        let producer = SignalProducerIndex::Registered(producer);
+        let consumer = SignalConsumerIndex::Registered(consumer);
+
+        let record = self.take_edge_record(producer, consumer);
+        if self.graph.has_edge(producer, consumer) {
+            self.graph.remove_edge(producer, consumer);
+        } else if self.feedback_edges.contains_key(&(producer, consumer)) {
+            self.remove_feedback_edge(producer, consumer);
+        } else {
+            return None;
+        }
+        self.preferred_feedbacks.remove(&(producer, consumer));
+        self.forget_edge(*consumer.must_registered());
+        self.notify_disconnected(&[(producer, consumer)]);
+        self.emit(Event::EdgeRemoved(record.producer, record.consumer));
+
+        self.refresh();
+
+        Some(Edit::RestoreEdge(record))
+    }
+
+    // Collect everything attached to the edge, so it can be later restored.
+    // The transform is moved out of the graph.
+    fn take_edge_record(
+        &mut self,
+        producer: SignalProducerIndex<PI>,
+        consumer: SignalConsumerIndex<CI>,
+    ) -> EdgeRecord<PI, CI, N::Payload> {
+        let preferred = self.preferred_feedbacks.contains(&(producer, consumer));
+        let producer = *producer.must_registered();
+        let consumer = *consumer.must_registered();
+        let transform = if self.has_edge(producer, consumer) {
+            self.transforms.remove(&consumer)
+        } else {
+            None
+        };
+        EdgeRecord {
+            producer,
+            consumer,
+            preferred,
+            transform,
+        }
+    }
+
+    // Add back an edge previously removed. Returns false if that was not
+    // possible, e.g. because its port is no longer offered.
+    fn restore_edge(&mut self, record: EdgeRecord<PI, CI, N::Payload>) -> bool {
+        let EdgeRecord {
+            producer,
+            consumer,
+            preferred,
+            transform,
+        } = record;
+
+        if self.add_edge(producer, consumer).is_err() {
+            return false;
+        }
+        if preferred {
+            self.set_feedback_preference(producer, consumer, true);
+        }
+        if let Some(transform) = transform {
+            self.transforms.insert(consumer, transform);
+            self.refresh_tick_edges();
+        }
+        true
+    }
+
+    /// Compare the current topology of the graph with the given target.
+    ///
+    /// Registered nodes which are not kept by the target are to be removed.
+    /// Edges between kept nodes are compared with edges of the target. See the
+    /// [`diff`](../diff/index.html) module to learn more.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let diff = graph.diff(&target);
+    /// for node_index in diff.removed_nodes() {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn diff(&self, target: &Target<N, NI>) -> Diff<NI, PI, CI> {
+        let mut removed_nodes: Vec<_> = self
+            .graph
+            .nodes
+            .keys()
+            .filter_map(|node_index| match node_index {
+                SignalNodeIndex::Registered(node_index) => Some(*node_index),
+                _ => None,
+            })
+            .filter(|node_index| !target.kept_nodes.contains(node_index))
+            .collect();
+        removed_nodes.sort_by_key(|node_index| node_index.index());
+
+        let target_edges: HashSet<_> = target
+            .edges
+            .iter()
+            .filter_map(|edge| match (edge.producer_node, edge.consumer_node) {
+                (NodeRef::Existing(producer_node), NodeRef::Existing(consumer_node)) => Some((
+                    producer_node.producer(edge.producer),
+                    consumer_node.consumer(edge.consumer),
+                )),
+                _ => None,
+            })
+            .collect();
+
+        let mut removed_edges: Vec<_> = self
+            .registered_edges()
+            .into_iter()
+            .map(|(producer, consumer)| (*producer.must_registered(), *consumer.must_registered()))
+            .filter(|(producer, consumer)| {
+                target.kept_nodes.contains(&producer.node_index())
+                    && target.kept_nodes.contains(&consumer.node_index())
+                    && !target_edges.contains(&(*producer, *consumer))
+            })
+            .collect();
+        removed_edges.sort_by_key(|(producer, consumer)| {
+            (producer.node_index().index(), consumer.node_index().index())
+        });
+
+        let added_edges = target
+            .edges
+            .iter()
+            .filter(|edge| match (edge.producer_node, edge.consumer_node) {
+                (NodeRef::Existing(producer_node), NodeRef::Existing(consumer_node)) => !self
+                    .has_edge(
+                        producer_node.producer(edge.producer),
+                        consumer_node.consumer(edge.consumer),
+                    ),
+                _ => true,
+            })
+            .copied()
+            .collect();
+
+        Diff {
+            removed_nodes,
+            added_nodes: target.new_nodes.len(),
+            removed_edges,
+            added_edges,
+        }
+    }
+
+    /// Move from the current topology of the graph to the given target with
+    /// the fewest changes.
+    ///
+    /// Kept nodes are left untouched, preserving their state. All the changes
+    /// are applied in a single batch, so feedbacks and the order of nodes are
+    /// recomputed only once. With the [history](#method.enable_history)
+    /// enabled, the whole batch is reverted by a single
+    /// [`undo`](#method.undo). Returns indices of the new nodes, in the order
+    /// in which they were added to the target.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any of the kept nodes is not present in the
+    /// graph or if any of the edges of the target cannot be added, see
+    /// [`add_edge`](#method.add_edge) for possible reasons. The target is
+    /// validated as a whole before any change is done, so the graph is left
+    /// untouched on error.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut target = Target::new();
+    /// let generator = target.keep_node(generator);
+    /// let echo = target.add_node(Echo::default());
+    /// target.add_edge(generator, GeneratorProducer, echo, EchoConsumer)?;
+    /// let new_nodes = graph.apply(target)?;
+    /// ```
+    pub fn apply(&mut self, target: Target<N, NI>) -> Result<Vec<NI>, Error> {
+        self.check_target(&target)?;
+        let diff = self.diff(&target);
+
+        self.deferred = true;
+        self.batch = Some(Vec::new());
+        let added_nodes = self.apply_diff(diff, target.new_nodes);
+        let batch = self.batch.take().unwrap();
+        self.deferred = false;
+        self.refresh();
+
+        if !batch.is_empty() {
+            self.record(Edit::Batch(batch));
+        }
+
+        Ok(added_nodes)
+    }
+
+    // Check that the whole target can be applied, so the graph is never left
+    // changed half-way.
+    fn check_target(&self, target: &Target<N, NI>) -> Result<(), Error> {
+        for node_index in target.kept_nodes.iter() {
+            let signal_index = SignalNodeIndex::Registered(*node_index);
+            if self.graph.node(&signal_index).is_none() {
+                return Err(self.graph.missing_node_error(&signal_index));
             }
         }
 
-        fn producer<IntoP>(&self, producer: IntoP) -> Self::ProducerIndex
-        where
-            IntoP: Into<TestProducer>,
-        {
-            let producer = producer.into();
-            match self.class {
-                Self::Class::Generator => match producer {
-                    Self::Producer::Generator(_) => {
-                        Self::ProducerIndex::new(*self, producer.into())
-                    }
-                    _ => panic!("Node does not offer such producer"),
-                },
-                Self::Class::Sum => match producer {
-                    Self::Producer::Sum(_) => Self::ProducerIndex::new(*self, producer.into()),
-                    _ => panic!("Node does not offer such producer"),
-                },
-                Self::Class::Recorder => match producer {
-                    Self::Producer::Recorder(_) => Self::ProducerIndex::new(*self, producer.into()),
-                    _ => panic!("Node does not offer such producer"),
-                },
+        let resolve = |node: NodeRef<NI>| match node {
+            NodeRef::Existing(node_index) if target.kept_nodes.contains(&node_index) => {
+                self.node(&node_index)
+            }
+            NodeRef::Existing(_) => None,
+            NodeRef::New(position) => target.new_nodes.get(position),
+        };
+        let refuse = |kind| Err(Error::AddEdgeError(kind));
+
+        let mut fed_consumers = HashSet::new();
+        for edge in target.edges.iter() {
+            let (producer_node, consumer_node) =
+                match (resolve(edge.producer_node), resolve(edge.consumer_node)) {
+                    (Some(producer_node), Some(consumer_node)) => (producer_node, consumer_node),
+                    _ => return refuse(AddEdgeErrorKind::NodeNotFound),
+                };
+            if !producer_node.offers_producer(edge.producer) {
+                return refuse(AddEdgeErrorKind::UnavailableProducer);
+            }
+            if !consumer_node.offers_consumer(edge.consumer) {
+                return refuse(AddEdgeErrorKind::UnavailableConsumer);
+            }
+            if let (Some(producer_type), Some(consumer_type)) = (
+                producer_node.producer_type(edge.producer),
+                consumer_node.consumer_type(edge.consumer),
+            ) {
+                if producer_type != consumer_type
+                    && !self
+                        .conversions
+                        .contains_key(&(producer_type, consumer_type))
+                {
+                    return refuse(AddEdgeErrorKind::IncompatibleTypes);
+                }
+            }
+            if !fed_consumers.insert((edge.consumer_node, edge.consumer)) {
+                return refuse(AddEdgeErrorKind::OccupiedConsumer);
+            }
+            if let NodeRef::Existing(node_index) = edge.consumer_node {
+                let consumer = node_index.consumer(edge.consumer);
+                if self.is_occupied(SignalConsumerIndex::Registered(consumer))
+                    && self.connected_producer(consumer).is_none()
+                {
+                    return refuse(AddEdgeErrorKind::ConsumerFedByInput);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Perform the changes of a validated target.
+    fn apply_diff(&mut self, diff: Diff<NI, PI, CI>, new_nodes: Vec<N>) -> Vec<NI> {
+        for node_index in diff.removed_nodes {
+            self.remove_node(node_index);
+        }
+        for (producer, consumer) in diff.removed_edges {
+            self.remove_edge(producer, consumer);
+        }
+
+        let added_nodes: Vec<_> = new_nodes
+            .into_iter()
+            .map(|node| self.add_node(node))
+            .collect();
+        let resolve = |node: NodeRef<NI>| match node {
+            NodeRef::Existing(node_index) => node_index,
+            NodeRef::New(position) => added_nodes[position],
+        };
+
+        for edge in diff.added_edges {
+            self.must_add_edge(
+                resolve(edge.producer_node()).producer(edge.producer()),
+                resolve(edge.consumer_node()).consumer(edge.consumer()),
+            );
+        }
+
+        added_nodes
+    }
+
+    /// Start recording changes of the graph topology, so they can be later
+    /// reverted through [`undo`](#method.undo).
+    ///
+    /// Adding and removing of nodes and edges is recorded. Removed nodes are
+    /// kept in the journal, so they can be brought back with their original
+    /// index, state, edges and edge transforms. Does nothing if the history
+    /// is already enabled.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.enable_history();
+    /// graph.remove_node(generator);
+    /// graph.undo();
+    /// assert!(graph.node(&generator).is_some());
+    /// ```
+    pub fn enable_history(&mut self) {
+        if self.history.is_none() {
+            self.history = Some(History::new());
+        }
+    }
+
+    /// Stop recording changes and drop the journal, including all the removed
+    /// nodes kept in it.
+    pub fn disable_history(&mut self) {
+        self.history = None;
+    }
+
+    /// Check whether there is a recorded change that can be undone.
+    pub fn can_undo(&self) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| history.can_undo())
+    }
+
+    /// Check whether there is an undone change that can be redone.
+    pub fn can_redo(&self) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| history.can_redo())
+    }
+
+    /// Revert the last recorded change of the topology.
+    ///
+    /// Returns `false` if there was nothing to undo or the history is not
+    /// enabled. See [`enable_history`](#method.enable_history) to learn
+    /// more.
+    ///
+    /// A change that cannot be fully reverted, e.g. an edge leading to a
+    /// port which is no longer offered, is reverted only partially.
+    pub fn undo(&mut self) -> bool {
+        let mut history = match self.history.take() {
+            Some(history) => history,
+            None => return false,
+        };
+
+        let undone = match history.pop_undo() {
+            Some(edit) => {
+                if let Some(inverse) = self.apply_edit(edit) {
+                    history.push_redo(inverse);
+                }
+                true
+            }
+            None => false,
+        };
+
+        self.history = Some(history);
+        undone
+    }
+
+    /// Repeat the last change reverted by [`undo`](#method.undo).
+    ///
+    /// Returns `false` if there was nothing to redo or the history is not
+    /// enabled. Any new change of the topology drops the changes which could
+    /// be redone.
+    pub fn redo(&mut self) -> bool {
+        let mut history = match self.history.take() {
+            Some(history) => history,
+            None => return false,
+        };
+
+        let redone = match history.pop_redo() {
+            Some(edit) => {
+                if let Some(inverse) = self.apply_edit(edit) {
+                    history.push_undo(inverse);
+                }
+                true
+            }
+            None => false,
+        };
+
+        self.history = Some(history);
+        redone
+    }
+
+    fn record(&mut self, edit: Edit<N, NI, PI, CI, N::Payload>) {
+        if let Some(history) = &mut self.history {
+            match &mut self.batch {
+                Some(batch) => batch.push(edit),
+                None => history.record(edit),
+            }
+        }
+    }
+
+    // Perform the edit and return its inverse. Must be called while the
+    // history is taken out, so the performed changes are not recorded.
+    fn apply_edit(
+        &mut self,
+        edit: Edit<N, NI, PI, CI, N::Payload>,
+    ) -> Option<Edit<N, NI, PI, CI, N::Payload>> {
+        match edit {
+            Edit::RemoveNode(node_index) => self.take_node(node_index),
+            Edit::RestoreNode {
+                node_index,
+                node,
+                priority,
+                edges,
+                order_constraints,
+                normals,
+                disconnect_policies,
+            } => {
+                self.graph.insert_node(
+                    SignalNodeIndex::Registered(node_index),
+                    SignalNode::Registered(node),
+                );
+                self.set_tick_priority(node_index, priority);
+                self.disconnect_policies.extend(disconnect_policies);
+                self.refresh();
+                for (before, after) in order_constraints {
+                    self.order_constraints.insert((before, after));
+                    self.emit(Event::OrderConstraintAdded(before, after));
+                }
+                for edge in edges {
+                    self.restore_edge(edge);
+                }
+                for record in normals {
+                    let _ = self.replace_normal(record);
+                }
+                Some(Edit::RemoveNode(node_index))
+            }
+            Edit::RemoveEdge(producer, consumer) => self.take_edge(producer, consumer),
+            Edit::RestoreEdge(record) => {
+                let (producer, consumer) = (record.producer, record.consumer);
+                if self.restore_edge(record) {
+                    Some(Edit::RemoveEdge(producer, consumer))
+                } else {
+                    None
+                }
+            }
+            Edit::RemoveOrderConstraint(before, after) => {
+                if self.has_order_constraint(before, after) {
+                    self.remove_order_constraint(before, after);
+                    Some(Edit::RestoreOrderConstraint(before, after))
+                } else {
+                    None
+                }
             }
+            Edit::RestoreOrderConstraint(before, after) => self
+                .add_order_constraint(before, after)
+                .ok()
+                .map(|_| Edit::RemoveOrderConstraint(before, after)),
+            Edit::RemoveNormal(consumer) => {
+                let record = self.take_normal(consumer)?;
+                if !self.deferred {
+                    self.update_cache();
+                }
+                Some(Edit::RestoreNormal(record))
+            }
+            Edit::RestoreNormal(record) => self.replace_normal(record).ok(),
+            Edit::Batch(edits) => {
+                let deferred = core::mem::replace(&mut self.deferred, true);
+                let inverses = edits
+                    .into_iter()
+                    .rev()
+                    .filter_map(|edit| self.apply_edit(edit))
+                    .collect();
+                self.deferred = deferred;
+                if !deferred {
+                    self.refresh();
+                }
+                Some(Edit::Batch(inverses))
+            }
+        }
+    }
+
+    fn remove_feedback_edge(
+        &mut self,
+        producer: SignalProducerIndex<PI>,
+        consumer: SignalConsumerIndex<CI>,
+    ) {
+        let (source, sink) = self.feedback_edges.get(&(producer, consumer)).unwrap();
+        self.graph.remove_node(*source);
+        self.graph.remove_node(*sink);
+        self.feedback_edges.remove(&(producer, consumer));
+    }
+
+    /// Remove all edges attached to consumers or producers which are no longer
+    /// offered by the given node.
+    ///
+    /// This should be called after changing the number of ports of a node with
+    /// an indexed family of ports through
+    /// [`node_mut`](#method.node_mut). Prefer
+    /// [`modify_node`](#method.modify_node), which calls this right away. See
+    /// [`Node::offers_consumer`](../node/trait.Node.html#method.offers_consumer)
+    /// to learn more.
+    ///
+    /// Does nothing if the `node_index` does not match an existing node.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if let Some(__Node::Mixer(mixer)) = graph.node_mut(&mixer_index) {
+    ///     mixer.set_inputs(2);
+    /// }
+    /// graph.refresh_ports(mixer_index);
+    /// ```
+    pub fn refresh_ports(&mut self, node_index: NI) {
+        let node = match self.node(&node_index) {
+            Some(node) => node,
+            None => return,
+        };
+
+        let unavailable_edges: Vec<_> = self
+            .registered_edges()
+            .into_iter()
+            .map(|(producer, consumer)| (*producer.must_registered(), *consumer.must_registered()))
+            .filter(|(producer, consumer)| {
+                (producer.node_index() == node_index && !node.offers_producer(producer.producer()))
+                    || (consumer.node_index() == node_index
+                        && !node.offers_consumer(consumer.consumer()))
+            })
+            .collect();
+
+        for (producer, consumer) in unavailable_edges {
+            self.remove_edge(producer, consumer);
+        }
+    }
+
+    /// Mark the edge as a preferred place for a feedback delay.
+    ///
+    /// When the edge becomes a part of a cycle, it will be the one delayed,
+    /// instead of being picked by the default rule described in
+    /// [`SignalGraph`](struct.SignalGraph.html) documentation. Passing `false`
+    /// removes the mark again.
+    ///
+    /// Does nothing if there is no such edge present.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.set_feedback_preference(
+    ///     sum.producer(SumProducer),
+    ///     sum.consumer(SumConsumer::In2),
+    ///     true,
+    /// );
+    /// ```
+    pub fn set_feedback_preference(&mut self, producer: PI, consumer: CI, preferred: bool) {
+        if !self.has_edge(producer, consumer) {
+            return;
         }
+
+        let producer = SignalProducerIndex::Registered(producer);
+        let consumer = SignalConsumerIndex::Registered(consumer);
+
+        if preferred {
+            self.preferred_feedbacks.insert((producer, consumer));
+        } else {
+            self.preferred_feedbacks.remove(&(producer, consumer));
+        }
+
+        self.refresh();
+    }
+
+    /// Check whether the edge is marked as a preferred place for a feedback
+    /// delay.
+    ///
+    /// See [`set_feedback_preference`](#method.set_feedback_preference) for
+    /// more info.
+    pub fn is_feedback_preferred(&self, producer: PI, consumer: CI) -> bool {
+        let producer = SignalProducerIndex::Registered(producer);
+        let consumer = SignalConsumerIndex::Registered(consumer);
+
+        self.preferred_feedbacks.contains(&(producer, consumer))
+    }
+
+    // Decide which edges must be delayed to break all the cycles. Edges are
+    // accepted as direct one by one, ordered by their feedback priority. An
+    // edge that would close a cycle among already accepted edges gets delayed.
+    fn place_feedbacks(&mut self) {
+        let delayed_edges = self.find_delayed_edges(self.order_constraints.iter().copied());
+
+        let delayed_set: HashSet<_> = delayed_edges.iter().copied().collect();
+        let mut feedback_edges: Vec<_> = self.feedback_edges.keys().copied().collect();
+        feedback_edges.sort_by_key(|edge| self.feedback_priority(edge));
+        for (producer, consumer) in feedback_edges {
+            if !delayed_set.contains(&(producer, consumer)) {
+                self.remove_feedback_edge(producer, consumer);
+                self.graph.must_add_edge(producer, consumer);
+                self.notify_delay_change(consumer, false);
+                self.emit(Event::EdgeDirect(
+                    *producer.must_registered(),
+                    *consumer.must_registered(),
+                ));
+            }
+        }
+
+        for (producer, consumer) in delayed_edges {
+            if !self.feedback_edges.contains_key(&(producer, consumer)) {
+                self.graph.remove_edge(producer, consumer);
+                self.add_feedback_edge(producer, consumer);
+                self.notify_delay_change(consumer, true);
+                self.emit(Event::EdgeDelayed(
+                    *producer.must_registered(),
+                    *consumer.must_registered(),
+                ));
+            }
+        }
+    }
+
+    // Pick edges which must be delayed to break all cycles, given the order
+    // constraints.
+    fn find_delayed_edges(
+        &self,
+        order_constraints: impl Iterator<Item = (NI, NI)>,
+    ) -> Vec<(SignalProducerIndex<PI>, SignalConsumerIndex<CI>)> {
+        let mut edges = self.registered_edges();
+        edges.sort_by_key(|edge| self.feedback_priority(edge));
+
+        // Edges are accepted as direct one by one, unless their consumer
+        // already reaches their producer through direct edges accepted before.
+        // Edges between the same pair of nodes share their fate, so ties in
+        // the ordering do not affect the outcome.
+        let mut successors: HashMap<SignalNodeIndex<NI>, Vec<SignalNodeIndex<NI>>> = HashMap::new();
+        for (before, after) in order_constraints {
+            successors
+                .entry(SignalNodeIndex::Registered(before))
+                .or_default()
+                .push(SignalNodeIndex::Registered(after));
+        }
+        let mut delayed_edges = Vec::new();
+        for (producer, consumer) in edges {
+            let source = producer.node_index();
+            let destination = consumer.node_index();
+            if sort::reaches(&successors, destination, source) {
+                delayed_edges.push((producer, consumer));
+            } else {
+                successors.entry(source).or_default().push(destination);
+            }
+        }
+        delayed_edges
+    }
+
+    // List all edges connecting registered nodes, both direct and delayed.
+    fn registered_edges(&self) -> Vec<(SignalProducerIndex<PI>, SignalConsumerIndex<CI>)> {
+        self.graph
+            .edges
+            .iter()
+            .filter(|(producer, consumer)| {
+                matches!(producer, SignalProducerIndex::Registered(_))
+                    && matches!(consumer, SignalConsumerIndex::Registered(_))
+            })
+            .chain(self.feedback_edges.keys())
+            .copied()
+            .collect()
+    }
+
+    // Edges with lower priority are accepted as direct first, the ones with
+    // the highest priority are the first candidates for a delay.
+    fn feedback_priority(
+        &self,
+        edge: &(SignalProducerIndex<PI>, SignalConsumerIndex<CI>),
+    ) -> (bool, bool, usize, usize) {
+        let producer_index = edge.0.node_index().index();
+        let consumer_index = edge.1.node_index().index();
+        (
+            self.preferred_feedbacks.contains(edge),
+            producer_index >= consumer_index,
+            producer_index,
+            consumer_index,
+        )
+    }
+
+    /// Check whether the graph contains an edge connecting given producer and consumer.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.has_edge(
+    ///     generator.producer(GeneratorProducer),
+    ///     echo.consumer(EchoConsumer),
+    /// );
+    /// ```
+    pub fn has_edge(&self, producer: PI, consumer: CI) -> bool {
+        let producer = SignalProducerIndex::Registered(producer);
+        let consumer = SignalConsumerIndex::Registered(consumer);
+
+        self.graph.has_edge(producer, consumer)
+            || self.feedback_edges.contains_key(&(producer, consumer))
+    }
+
+    /// Check whether the edge connecting given producer and consumer delays
+    /// passed data by a single tick to break a cycle.
+    ///
+    /// Returns `false` if there is no such edge present.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.is_feedback_edge(
+    ///     sum.producer(SumProducer),
+    ///     sum.consumer(SumConsumer::In2),
+    /// );
+    /// ```
+    pub fn is_feedback_edge(&self, producer: PI, consumer: CI) -> bool {
+        let producer = SignalProducerIndex::Registered(producer);
+        let consumer = SignalConsumerIndex::Registered(consumer);
+
+        self.feedback_edges.contains_key(&(producer, consumer))
+    }
+
+    /// Iterate over all edges which are currently delaying passed data by a
+    /// single tick to break a cycle.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for (producer, consumer) in graph.feedback_edges() {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn feedback_edges(&self) -> impl Iterator<Item = (PI, CI)> + '_ {
+        self.feedback_edges
+            .keys()
+            .map(|(producer, consumer)| (*producer.must_registered(), *consumer.must_registered()))
+    }
+
+    /// Iterate over all edges between nodes of the graph, including those
+    /// delayed by a feedback. Edges of graph inputs and outputs are not
+    /// listed. The order of edges is not defined.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for (producer, consumer) in graph.edges() {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn edges(&self) -> impl Iterator<Item = (PI, CI)> + '_ {
+        self.registered_edges()
+            .into_iter()
+            .map(|(producer, consumer)| (*producer.must_registered(), *consumer.must_registered()))
+    }
+
+    /// List all the loops currently broken by a feedback edge.
+    ///
+    /// There is one loop listed per feedback edge. It is closed by the feedback
+    /// edge and otherwise consists of the shortest path of direct edges leading
+    /// from the consumer of the feedback edge back to its producer. This is
+    /// meant for debugging of unexpected feedbacks in the graph.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for feedback_loop in graph.feedback_loops() {
+    ///     let (producer, consumer) = feedback_loop.feedback();
+    ///     let nodes = feedback_loop.nodes();
+    /// }
+    /// ```
+    pub fn feedback_loops(&self) -> Vec<FeedbackLoop<NI, PI, CI>> {
+        let mut feedbacks: Vec<_> = self.feedback_edges().collect();
+        feedbacks.sort_by_key(|(producer, consumer)| {
+            (producer.node_index().index(), consumer.node_index().index())
+        });
+
+        feedbacks
+            .into_iter()
+            .filter_map(|(producer, consumer)| {
+                let mut edges = self.direct_path(consumer.node_index(), producer.node_index())?;
+                edges.push((producer, consumer));
+                let nodes = edges
+                    .iter()
+                    .map(|(producer, _)| producer.node_index())
+                    .collect();
+                Some(FeedbackLoop { nodes, edges })
+            })
+            .collect()
+    }
+
+    // Find the shortest path of direct edges leading between given nodes.
+    fn direct_path(&self, from: NI, to: NI) -> Option<Vec<(PI, CI)>> {
+        let mut previous_edges: HashMap<NI, (PI, CI)> = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+        visited.insert(from);
+        queue.push_back(from);
+
+        while let Some(node_index) = queue.pop_front() {
+            if node_index == to {
+                let mut path = Vec::new();
+                let mut current = to;
+                while current != from {
+                    let edge = previous_edges[&current];
+                    path.push(edge);
+                    current = edge.0.node_index();
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for (producer, consumer) in self.graph.edges.iter() {
+                if let (
+                    SignalProducerIndex::Registered(producer),
+                    SignalConsumerIndex::Registered(consumer),
+                ) = (producer, consumer)
+                {
+                    let next = consumer.node_index();
+                    if producer.node_index() == node_index && visited.insert(next) {
+                        previous_edges.insert(next, (*producer, *consumer));
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Start queuing [events](enum.Event.html) describing changes of the graph
+    /// topology, so they can be taken through
+    /// [`drain_events`](#method.drain_events).
+    ///
+    /// The queue is disabled by default. Once enabled, events are kept until
+    /// drained, so users interested in them should drain them regularly.
+    /// Observers registered through [`add_observer`](#method.add_observer)
+    /// are called regardless. Does nothing if the queue is already enabled.
+    pub fn enable_event_queue(&mut self) {
+        if self.events.is_none() {
+            self.events = Some(Vec::new());
+        }
+    }
+
+    /// Stop queuing events and drop those which were not drained yet.
+    pub fn disable_event_queue(&mut self) {
+        self.events = None;
+    }
+
+    /// Take all the [events](enum.Event.html) describing changes of the graph
+    /// topology since the last call.
+    ///
+    /// Events are queued only after
+    /// [`enable_event_queue`](#method.enable_event_queue), otherwise this
+    /// returns nothing. Changes done through [`undo`](#method.undo) and
+    /// [`redo`](#method.redo) are reported too.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.enable_event_queue();
+    /// graph.add_edge(generator.producer(GeneratorProducer), echo.consumer(EchoConsumer))?;
+    ///
+    /// for event in graph.drain_events() {
+    ///     match event {
+    ///         Event::EdgeDelayed(producer, consumer) => (),
+    ///         Event::EdgeDirect(producer, consumer) => (),
+    ///         _ => (),
+    ///     }
+    /// }
+    /// ```
+    pub fn drain_events(&mut self) -> impl Iterator<Item = Event<NI, PI, CI>> + '_ {
+        self.events.iter_mut().flat_map(|events| events.drain(..))
+    }
+
+    /// Register an observer called on every [event](enum.Event.html) right
+    /// when it happens.
+    ///
+    /// Observers are called before the event is queued for
+    /// [`drain_events`](#method.drain_events), if the queue is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.add_observer(|event| println!("{:?}", event));
+    /// ```
+    pub fn add_observer<F>(&mut self, observer: F)
+    where
+        F: FnMut(&Event<NI, PI, CI>) + 'static,
+    {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Remove all the registered observers.
+    pub fn clear_observers(&mut self) {
+        self.observers.clear();
+    }
+
+    fn emit(&mut self, event: Event<NI, PI, CI>) {
+        for observer in self.observers.iter_mut() {
+            observer(&event);
+        }
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+
+    /// Attach a transformation to the edge connecting given producer and
+    /// consumer.
+    ///
+    /// The transformation will be applied on the data while they are passed
+    /// from the producer to the consumer. It replaces any transformation
+    /// previously attached to the edge and it is dropped together with the
+    /// edge. This can be used to attenuate or offset the signal without
+    /// introducing a dedicated node. See the [`transform`](../transform/index.html)
+    /// module for available transformations.
+    ///
+    /// Does nothing if there is no such edge present.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.set_edge_transform(
+    ///     generator.producer(GeneratorProducer),
+    ///     echo.consumer(EchoConsumer),
+    ///     Scale(2),
+    /// );
+    /// ```
+    pub fn set_edge_transform<T>(&mut self, producer: PI, consumer: CI, transform: T)
+    where
+        T: Transform<N::Payload> + 'static,
+    {
+        if self.has_edge(producer, consumer)
+            && self.transforms.insert(consumer, Box::new(transform))
+        {
+            self.refresh_tick_edges();
+        }
+    }
+
+    /// Detach transformation from the edge connecting given producer and
+    /// consumer, letting the data pass unchanged.
+    ///
+    /// Does nothing if there is no such edge present.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.remove_edge_transform(
+    ///     generator.producer(GeneratorProducer),
+    ///     echo.consumer(EchoConsumer),
+    /// );
+    /// ```
+    pub fn remove_edge_transform(&mut self, producer: PI, consumer: CI) {
+        if self.has_edge(producer, consumer) && self.transforms.remove(&consumer).is_some() {
+            self.refresh_tick_edges();
+        }
+    }
+
+    /// Check whether the edge connecting given producer and consumer has a
+    /// transformation attached.
+    pub fn has_edge_transform(&self, producer: PI, consumer: CI) -> bool {
+        self.has_edge(producer, consumer) && self.transforms.contains_key(&consumer)
+    }
+
+    // Find the producer feeding the given consumer, be it directly or through
+    // a feedback.
+    fn connected_producer(&self, consumer: CI) -> Option<PI> {
+        let consumer = SignalConsumerIndex::Registered(consumer);
+        self.graph
+            .edges
+            .iter()
+            .chain(self.feedback_edges.keys())
+            .filter(|(_, existing_consumer)| *existing_consumer == consumer)
+            .find_map(|(producer, _)| match producer {
+                SignalProducerIndex::Registered(producer) => Some(*producer),
+                _ => None,
+            })
+    }
+
+    // Remove an input or output of the graph, letting nodes know about the
+    // dropped edges.
+    fn remove_internal_node(&mut self, node_index: SignalNodeIndex<NI>) {
+        let disconnected = self.logical_edges_of(node_index);
+        self.graph.remove_node(node_index);
+        for (_, consumer) in disconnected.iter() {
+            if let SignalConsumerIndex::Registered(consumer) = consumer {
+                self.edge_conversions.remove(consumer);
+            }
+        }
+        self.notify_disconnected(&disconnected);
+        self.refresh();
+    }
+
+    // List edges as they were added by the user, including those of graph
+    // inputs and outputs. Edges delayed by a feedback are listed as a single
+    // edge, not as the pair of edges leading through the feedback nodes.
+    fn logical_edges(&self) -> Vec<(SignalProducerIndex<PI>, SignalConsumerIndex<CI>)> {
+        self.iter_logical_edges().collect()
+    }
+
+    fn iter_logical_edges(
+        &self,
+    ) -> impl Iterator<Item = (SignalProducerIndex<PI>, SignalConsumerIndex<CI>)> + '_ {
+        self.graph
+            .edges
+            .iter()
+            .filter(|(producer, consumer)| {
+                !matches!(
+                    producer.producer(),
+                    SignalProducer::Internal(InternalProducer::FeedbackSink(_))
+                ) && !matches!(
+                    consumer.consumer(),
+                    SignalConsumer::Internal(InternalConsumer::FeedbackSource(_))
+                )
+            })
+            .chain(self.feedback_edges.keys())
+            .copied()
+    }
+
+    fn logical_edges_of(
+        &self,
+        node_index: SignalNodeIndex<NI>,
+    ) -> Vec<(SignalProducerIndex<PI>, SignalConsumerIndex<CI>)> {
+        self.logical_edges()
+            .into_iter()
+            .filter(|(producer, consumer)| {
+                producer.node_index() == node_index || consumer.node_index() == node_index
+            })
+            .collect()
+    }
+
+    // Let nodes know about a newly added edge. Producers are notified only
+    // about their first edge.
+    fn notify_connected(
+        &mut self,
+        producer: SignalProducerIndex<PI>,
+        consumer: SignalConsumerIndex<CI>,
+    ) {
+        if let Some(node) = self.graph.node_mut(&consumer.node_index()) {
+            node.on_connect(consumer.consumer());
+        }
+        let producer_edges = self
+            .iter_logical_edges()
+            .filter(|(existing_producer, _)| *existing_producer == producer)
+            .take(2)
+            .count();
+        if producer_edges == 1 {
+            if let Some(node) = self.graph.node_mut(&producer.node_index()) {
+                node.on_connect_producer(producer.producer());
+            }
+        }
+    }
+
+    // Let nodes know about removed edges. Must be called after the removal,
+    // while the nodes are still present. Producers are notified only once
+    // they are left without any edge.
+    fn notify_disconnected(
+        &mut self,
+        edges: &[(SignalProducerIndex<PI>, SignalConsumerIndex<CI>)],
+    ) {
+        let mut producers = Vec::new();
+        let mut seen_producers = HashSet::new();
+        for (producer, consumer) in edges {
+            if let SignalConsumerIndex::Registered(consumer) = consumer {
+                self.apply_disconnect_policy(*consumer);
+            }
+            if let Some(node) = self.graph.node_mut(&consumer.node_index()) {
+                if node.offers_consumer(consumer.consumer()) {
+                    node.on_disconnect(consumer.consumer());
+                }
+            }
+            if seen_producers.insert(*producer) {
+                producers.push(*producer);
+            }
+        }
+        let connected_producers: HashSet<_> = self
+            .iter_logical_edges()
+            .map(|(producer, _)| producer)
+            .collect();
+        for producer in producers {
+            if !connected_producers.contains(&producer) {
+                if let Some(node) = self.graph.node_mut(&producer.node_index()) {
+                    if node.offers_producer(producer.producer()) {
+                        node.on_disconnect_producer(producer.producer());
+                    }
+                }
+            }
+        }
+    }
+
+    // Let the consumer know that its edge was moved behind or out of a
+    // feedback delay.
+    fn notify_delay_change(&mut self, consumer: SignalConsumerIndex<CI>, delayed: bool) {
+        if let Some(node) = self.graph.node_mut(&consumer.node_index()) {
+            node.on_delay_change(consumer.consumer(), delayed);
+        }
+    }
+
+    // Consumers no longer offered by their node, e.g. after `refresh_ports`,
+    // are left untouched.
+    fn apply_disconnect_policy(&mut self, consumer: CI) {
+        match self.node(&consumer.node_index()) {
+            Some(node) if node.offers_consumer(consumer.consumer()) => (),
+            _ => return,
+        }
+        let value = match self.disconnect_policy(consumer) {
+            DisconnectPolicy::Keep => return,
+            DisconnectPolicy::Reset => N::Payload::default(),
+            DisconnectPolicy::Value(value) => value,
+        };
+        if let Some(node) = self.node_mut(&consumer.node_index()) {
+            node.write(consumer.consumer(), value);
+        }
+    }
+
+    // Check whether the consumer is fed by any producer, including internal
+    // ones.
+    fn is_occupied(&self, consumer: SignalConsumerIndex<CI>) -> bool {
+        self.graph
+            .edges
+            .iter()
+            .chain(self.feedback_edges.keys())
+            .any(|(_, existing_consumer)| *existing_consumer == consumer)
+    }
+
+    // Drop all the data attached to the edge leading to the given consumer.
+    fn forget_edge(&mut self, consumer: CI) {
+        self.transforms.remove(&consumer);
+        self.edge_conversions.remove(&consumer);
+    }
+
+    /// Set what happens with the value of the consumer after its edge is
+    /// removed, overriding the policy declared by its node.
+    ///
+    /// See
+    /// [`Node::disconnect_policy`](../node/trait.Node.html#method.disconnect_policy)
+    /// to learn more.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.set_disconnect_policy(echo.consumer(EchoConsumer), DisconnectPolicy::Reset);
+    /// ```
+    pub fn set_disconnect_policy(&mut self, consumer: CI, policy: DisconnectPolicy<N::Payload>) {
+        self.disconnect_policies.insert(consumer, policy);
+    }
+
+    /// Drop the policy set through
+    /// [`set_disconnect_policy`](#method.set_disconnect_policy), falling back
+    /// to the one declared by the node.
+    pub fn remove_disconnect_policy(&mut self, consumer: CI) {
+        self.disconnect_policies.remove(&consumer);
+    }
+
+    /// Get the policy applied on the consumer after its edge is removed.
+    ///
+    /// Returns `DisconnectPolicy::Keep` if the node of the consumer is not
+    /// present in the graph.
+    pub fn disconnect_policy(&self, consumer: CI) -> DisconnectPolicy<N::Payload> {
+        if let Some(policy) = self.disconnect_policies.get(&consumer) {
+            return *policy;
+        }
+        self.node(&consumer.node_index())
+            .map(|node| node.disconnect_policy(consumer.consumer()))
+            .unwrap_or(DisconnectPolicy::Keep)
+    }
+
+    /// Traverse the whole graph and tick all present nodes, passing data
+    /// through registered edges.
+    ///
+    /// A single tick here performs a single iteration through the whole graph.
+    /// It triggers tick of each node, gathers generated data from registered
+    /// producers, passes the data to connected consumers and continues with the
+    /// transition.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.tick();
+    /// // Echo: 1
+    /// graph.tick();
+    /// // Echo: 1
+    /// ```
+    pub fn tick(&mut self) {
+        if !self.modified_nodes.is_empty() {
+            self.update_offered_ports();
+        }
+
+        for (node_index, edges) in self.sorted_nodes.iter().zip(self.tick_edges.iter()) {
+            match self.graph.nodes.get_mut(node_index) {
+                Some(node) => node.tick(),
+                None => continue,
+            }
+
+            for edge in edges.iter().filter(|edge| edge.offered) {
+                let source = match self.graph.nodes.get(node_index) {
+                    Some(source) => source,
+                    None => continue,
+                };
+                let mut output = source.read(edge.producer.producer());
+                let conversions = &mut self.conversions;
+                if let Some(conversion) = edge
+                    .conversion
+                    .and_then(|conversion| conversions.get_mut(conversion))
+                {
+                    output = conversion.apply(output);
+                }
+                let transform = match edge.transform {
+                    Some(TickTransform::Edge(slot)) => self.transforms.get_mut(slot),
+                    Some(TickTransform::Normal(slot)) => self.normal_transforms.get_mut(slot),
+                    None => None,
+                };
+                if let Some(transform) = transform {
+                    output = transform.apply(output);
+                }
+                if let Some(destination) = self.graph.nodes.get_mut(&edge.consumer.node_index()) {
+                    destination.write(edge.consumer.consumer(), output);
+                }
+            }
+        }
+    }
+
+    /// Reset state of all the nodes in the graph and clear data delayed by
+    /// feedback edges.
+    ///
+    /// Each registered node gets its
+    /// [`Node::reset`](../node/trait.Node.html#method.reset) called. Values
+    /// held by feedback edges and external outputs are set back to the default
+    /// of the payload. Values set on external inputs are kept. The topology of
+    /// the graph is not changed.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// graph.reset();
+    /// ```
+    pub fn reset(&mut self) {
+        for node in self.graph.nodes.values_mut() {
+            node.reset();
+        }
+    }
+
+    // Place feedbacks and recompute the order of nodes after a change of the
+    // topology, unless the change is a part of a batch.
+    fn refresh(&mut self) {
+        if !self.deferred {
+            self.place_feedbacks();
+            self.update_cache();
+        }
+    }
+
+    // Resolve conversions and transformations passed to `tick` again after
+    // they changed without a change of the topology, unless the change is a
+    // part of a batch.
+    fn refresh_tick_edges(&mut self) {
+        if !self.deferred {
+            self.update_tick_edges();
+        }
+    }
+
+    fn update_cache(&mut self) {
+        let active_normals = self.find_active_normals();
+
+        // Normalled producers are accepted one by one, like edges in
+        // `find_delayed_edges`. Those closing a cycle are not taken into
+        // account, so their data are delayed by a tick.
+        let mut successors: HashMap<SignalNodeIndex<NI>, Vec<SignalNodeIndex<NI>>> = HashMap::new();
+        let edges = self
+            .graph
+            .edges
+            .iter()
+            .map(|(producer, consumer)| (producer.node_index(), consumer.node_index()));
+        let order_constraints = self.order_constraints.iter().map(|(before, after)| {
+            (
+                SignalNodeIndex::Registered(*before),
+                SignalNodeIndex::Registered(*after),
+            )
+        });
+        for (source, destination) in edges.chain(order_constraints) {
+            successors.entry(source).or_default().push(destination);
+        }
+        let mut normal_edges = Vec::new();
+        for (producer, consumer, _) in active_normals.iter() {
+            let source = SignalNodeIndex::Registered(producer.node_index());
+            let destination = SignalNodeIndex::Registered(consumer.node_index());
+            if !sort::reaches(&successors, destination, source) {
+                successors.entry(source).or_default().push(destination);
+                normal_edges.push((producer.node_index(), consumer.node_index()));
+            }
+        }
+
+        let sorted_nodes = match self.topologically_sorted_nodes(&normal_edges) {
+            Ok(sorted_nodes) => sorted_nodes,
+            Err(_) => panic!("Feedbacks must break all cycles"),
+        };
+        self.active_normals = active_normals;
+        if sorted_nodes != self.sorted_nodes {
+            self.sorted_nodes = sorted_nodes;
+            self.emit(Event::OrderRecomputed);
+        }
+        self.update_tick_edges();
+    }
+
+    // Group edges and active normalled producers by the node feeding them, in
+    // the order of `sorted_nodes`, so `tick` does not need to look up their
+    // conversions and transformations.
+    fn update_tick_edges(&mut self) {
+        let positions: HashMap<_, _> = self
+            .sorted_nodes
+            .iter()
+            .enumerate()
+            .map(|(position, node_index)| (*node_index, position))
+            .collect();
+        let mut tick_edges: Vec<Vec<TickEdge<PI, CI>>> =
+            self.sorted_nodes.iter().map(|_| Vec::new()).collect();
+
+        for (producer, consumer) in self.graph.edges.iter() {
+            let (conversion, transform) = match consumer {
+                SignalConsumerIndex::Registered(consumer) => (
+                    self.edge_conversions.get(consumer),
+                    self.transforms.slot(consumer).map(TickTransform::Edge),
+                ),
+                SignalConsumerIndex::Internal(consumer) => {
+                    let output = GraphOutput {
+                        node_index: consumer.node_index(),
+                    };
+                    (self.output_conversions.get(&output), None)
+                }
+            };
+            if let Some(position) = positions.get(&producer.node_index()) {
+                tick_edges[*position].push(TickEdge {
+                    producer: *producer,
+                    consumer: *consumer,
+                    conversion: conversion.and_then(|conversion| self.conversions.slot(conversion)),
+                    transform,
+                    offered: false,
+                });
+            }
+        }
+
+        for (producer, consumer, conversion) in self.active_normals.iter() {
+            let source = SignalNodeIndex::Registered(producer.node_index());
+            if let Some(position) = positions.get(&source) {
+                tick_edges[*position].push(TickEdge {
+                    producer: SignalProducerIndex::Registered(*producer),
+                    consumer: SignalConsumerIndex::Registered(*consumer),
+                    conversion: conversion
+                        .as_ref()
+                        .and_then(|conversion| self.conversions.slot(conversion)),
+                    transform: self
+                        .normal_transforms
+                        .slot(consumer)
+                        .map(TickTransform::Normal),
+                    offered: false,
+                });
+            }
+        }
+
+        for edge in tick_edges.iter_mut().flatten() {
+            edge.offered = self.offers_ports(edge.producer, edge.consumer);
+        }
+        self.tick_edges = tick_edges;
+        self.modified_nodes.clear();
+    }
+
+    // Nodes may stop offering ports before their edges are refreshed, see
+    // `refresh_ports`. Edges of nodes accessed through `node_mut` are checked
+    // again before the next `tick`.
+    fn update_offered_ports(&mut self) {
+        let modified_nodes = core::mem::take(&mut self.modified_nodes);
+        let touches_modified = |node_index: SignalNodeIndex<NI>| match node_index {
+            SignalNodeIndex::Registered(node_index) => modified_nodes.contains(&node_index),
+            SignalNodeIndex::Internal(_) => false,
+        };
+        let mut tick_edges = core::mem::take(&mut self.tick_edges);
+        for edge in tick_edges.iter_mut().flatten() {
+            if touches_modified(edge.producer.node_index())
+                || touches_modified(edge.consumer.node_index())
+            {
+                edge.offered = self.offers_ports(edge.producer, edge.consumer);
+            }
+        }
+        self.tick_edges = tick_edges;
+    }
+
+    fn offers_ports(
+        &self,
+        producer: SignalProducerIndex<PI>,
+        consumer: SignalConsumerIndex<CI>,
+    ) -> bool {
+        let offers_producer = self
+            .graph
+            .node(&producer.node_index())
+            .is_some_and(|node| node.offers_producer(producer.producer()));
+        let offers_consumer = self
+            .graph
+            .node(&consumer.node_index())
+            .is_some_and(|node| node.offers_consumer(consumer.consumer()));
+        offers_producer && offers_consumer
+    }
+
+    fn topologically_sorted_nodes(
+        &self,
+        normal_edges: &[(NI, NI)],
+    ) -> Result<Vec<SignalNodeIndex<NI>>, sort::Cycle<SignalNodeIndex<NI>>> {
+        let nodes = self.graph.nodes.keys().copied();
+        let edges = self
+            .graph
+            .edges
+            .iter()
+            .map(|(producer, consumer)| (producer.node_index(), consumer.node_index()))
+            .chain(
+                self.order_constraints
+                    .iter()
+                    .chain(normal_edges.iter())
+                    .map(|(before, after)| {
+                        (
+                            SignalNodeIndex::Registered(*before),
+                            SignalNodeIndex::Registered(*after),
+                        )
+                    }),
+            );
+        sort::topological_sort(nodes, edges, |node_index| {
+            let priority = match node_index {
+                SignalNodeIndex::Registered(node_index) => self.tick_priority(*node_index),
+                SignalNodeIndex::Internal(_) => 0,
+            };
+            (Reverse(priority), node_index.index())
+        })
+    }
+}
+
+impl<N, NI, CI, PI> SignalGraph<N, NI, CI, PI>
+where
+    N: NodeWrapper<Class = NI::Class, Consumer = NI::Consumer, Producer = NI::Producer>,
+    NI: NodeIndex<ConsumerIndex = CI, ProducerIndex = PI>,
+    NI::Class: fmt::Debug,
+    NI::Consumer: fmt::Debug,
+    NI::Producer: fmt::Debug,
+    CI: ConsumerIndex<NodeIndex = NI, Consumer = NI::Consumer>,
+    PI: ProducerIndex<NodeIndex = NI, Producer = NI::Producer>,
+{
+    /// Describe the graph in the Graphviz DOT language.
+    ///
+    /// Each node is drawn as a record listing its consumers above and its
+    /// producers below the name of the node. Ports are labeled by names
+    /// provided through [`Node::consumers`](../node/trait.Node.html#method.consumers)
+    /// and [`Node::producers`](../node/trait.Node.html#method.producers),
+    /// connected ports without a description are labeled by their debug
+    /// representation. Edges delayed by a feedback are drawn dashed. External
+    /// inputs and outputs of the graph are drawn as standalone nodes.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// std::fs::write("graph.dot", graph.to_dot())?;
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        self.write_dot(&mut dot)
+            .expect("Writing into a string cannot fail");
+        dot
+    }
+
+    fn write_dot(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        let mut edges = self.logical_edges();
+
+        let mut node_indices: Vec<_> = self.graph.nodes.keys().copied().collect();
+        node_indices.sort_by_key(|node_index| node_index.index());
+
+        let mut consumer_ports = HashMap::new();
+        let mut producer_ports = HashMap::new();
+
+        writeln!(w, "digraph {{")?;
+        writeln!(w, "    node [shape=record];")?;
+
+        for node_index in node_indices {
+            let id = node_index.index();
+            match node_index {
+                SignalNodeIndex::Registered(registered_index) => {
+                    let node = self.graph.node(&node_index).unwrap().must_registered();
+
+                    let mut consumers: Vec<_> = node
+                        .consumers()
+                        .into_iter()
+                        .map(|port| (*port.port(), port.label()))
+                        .collect();
+                    for (_, consumer) in edges.iter() {
+                        if let SignalConsumerIndex::Registered(consumer) = consumer {
+                            if consumer.node_index() == registered_index
+                                && !consumers.iter().any(|(c, _)| *c == consumer.consumer())
+                            {
+                                consumers.push((
+                                    consumer.consumer(),
+                                    format!("{:?}", consumer.consumer()),
+                                ));
+                            }
+                        }
+                    }
+
+                    let mut producers: Vec<_> = node
+                        .producers()
+                        .into_iter()
+                        .map(|port| (*port.port(), port.label()))
+                        .collect();
+                    for (producer, _) in edges.iter() {
+                        if let SignalProducerIndex::Registered(producer) = producer {
+                            if producer.node_index() == registered_index
+                                && !producers.iter().any(|(p, _)| *p == producer.producer())
+                            {
+                                producers.push((
+                                    producer.producer(),
+                                    format!("{:?}", producer.producer()),
+                                ));
+                            }
+                        }
+                    }
+
+                    let mut label = String::new();
+                    if !consumers.is_empty() {
+                        label.push_str(&record_ports("c", &consumers));
+                        label.push('|');
+                    }
+                    label.push_str(&escape_record(&format!("{:?} #{}", node.class(), id)));
+                    if !producers.is_empty() {
+                        label.push('|');
+                        label.push_str(&record_ports("p", &producers));
+                    }
+                    writeln!(w, "    n{} [label=\"{{{}}}\"];", id, label)?;
+
+                    for (i, (consumer, _)) in consumers.into_iter().enumerate() {
+                        consumer_ports.insert(CI::new(registered_index, consumer), i);
+                    }
+                    for (i, (producer, _)) in producers.into_iter().enumerate() {
+                        producer_ports.insert(PI::new(registered_index, producer), i);
+                    }
+                }
+                SignalNodeIndex::Internal(internal_index) => {
+                    let input = GraphInput {
+                        node_index: internal_index,
+                    };
+                    let output = GraphOutput {
+                        node_index: internal_index,
+                    };
+                    if let Some((name, _)) = self.inputs.get(&input) {
+                        writeln!(
+                            w,
+                            "    n{} [shape=invhouse, label=\"{}\"];",
+                            id,
+                            escape_quoted(name)
+                        )?;
+                    } else if let Some((name, _)) = self.outputs.get(&output) {
+                        writeln!(
+                            w,
+                            "    n{} [shape=house, label=\"{}\"];",
+                            id,
+                            escape_quoted(name)
+                        )?;
+                    }
+                }
+            }
+        }
+
+        let producer_port = |producer: &SignalProducerIndex<PI>| match producer {
+            SignalProducerIndex::Registered(producer) => Some(producer_ports[producer]),
+            SignalProducerIndex::Internal(_) => None,
+        };
+        let consumer_port = |consumer: &SignalConsumerIndex<CI>| match consumer {
+            SignalConsumerIndex::Registered(consumer) => Some(consumer_ports[consumer]),
+            SignalConsumerIndex::Internal(_) => None,
+        };
+        edges.sort_by_key(|(producer, consumer)| {
+            (
+                producer.node_index().index(),
+                producer_port(producer),
+                consumer.node_index().index(),
+                consumer_port(consumer),
+            )
+        });
+
+        for (producer, consumer) in edges {
+            write!(w, "    n{}", producer.node_index().index())?;
+            if let Some(port) = producer_port(&producer) {
+                write!(w, ":p{}:s", port)?;
+            }
+            write!(w, " -> n{}", consumer.node_index().index())?;
+            if let Some(port) = consumer_port(&consumer) {
+                write!(w, ":c{}:n", port)?;
+            }
+            if self.feedback_edges.contains_key(&(producer, consumer)) {
+                write!(w, " [style=dashed, label=\"delayed\"]")?;
+            }
+            writeln!(w, ";")?;
+        }
+
+        writeln!(w, "}}")
+    }
+}
+
+fn record_ports<P>(prefix: &str, ports: &[(P, String)]) -> String {
+    let fields: Vec<_> = ports
+        .iter()
+        .enumerate()
+        .map(|(i, (_, label))| format!("<{}{}> {}", prefix, i, escape_record(label)))
+        .collect();
+    format!("{{{}}}", fields.join("|"))
+}
+
+// Record labels give a special meaning to braces, bars and angle brackets.
+fn escape_record(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn escape_quoted(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Stored form of the graph. Feedback edges are placed again on load. Since
+// node indices and feedback preferences are restored, they end up on the same
+// edges and only the data they delay need to be stored. The index counter is
+// stored so indices of removed nodes are not issued again after load.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Snapshot<N, NI, PI, CI, T> {
+    index_counter: usize,
+    nodes: Vec<(NI, N)>,
+    edges: Vec<(PI, CI)>,
+    delayed_data: Vec<(PI, CI, T)>,
+    preferred_feedbacks: Vec<(PI, CI)>,
+    tick_priorities: Vec<(NI, i32)>,
+    order_constraints: Vec<(NI, NI)>,
+    normals: Vec<(CI, PI)>,
+}
+
+/// Store nodes of the graph, their edges, data delayed by feedback edges,
+/// feedback preferences, tick priorities, order constraints and normalled
+/// producers.
+///
+/// Edge and normal transforms, registered conversions, disconnect policies,
+/// external inputs and outputs, observers and the history are not stored.
+#[cfg(feature = "serde")]
+impl<N, NI, CI, PI> serde::Serialize for SignalGraph<N, NI, CI, PI>
+where
+    N: NodeWrapper<Class = NI::Class, Consumer = NI::Consumer, Producer = NI::Producer>
+        + serde::Serialize,
+    N::Payload: serde::Serialize,
+    NI: NodeIndex<ConsumerIndex = CI, ProducerIndex = PI> + serde::Serialize,
+    CI: ConsumerIndex<NodeIndex = NI, Consumer = NI::Consumer> + serde::Serialize,
+    PI: ProducerIndex<NodeIndex = NI, Producer = NI::Producer> + serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let edge_key = |(producer, consumer): &(PI, CI)| {
+            (producer.node_index().index(), consumer.node_index().index())
+        };
+
+        let mut nodes: Vec<_> = self
+            .graph
+            .nodes
+            .iter()
+            .filter_map(|(node_index, node)| match (node_index, node) {
+                (SignalNodeIndex::Registered(node_index), SignalNode::Registered(node)) => {
+                    Some((*node_index, node))
+                }
+                _ => None,
+            })
+            .collect();
+        nodes.sort_by_key(|(node_index, _)| node_index.index());
+
+        let mut edges: Vec<_> = self
+            .registered_edges()
+            .into_iter()
+            .map(|(producer, consumer)| (*producer.must_registered(), *consumer.must_registered()))
+            .collect();
+        edges.sort_by_key(edge_key);
+
+        let mut delayed_data: Vec<_> = self
+            .feedback_edges
+            .iter()
+            .filter_map(|((producer, consumer), (_, sink))| {
+                let data = self.graph.node(sink)?.read(FeedbackSinkProducer);
+                Some((
+                    *producer.must_registered(),
+                    *consumer.must_registered(),
+                    data,
+                ))
+            })
+            .collect();
+        delayed_data.sort_by_key(|(producer, consumer, _)| edge_key(&(*producer, *consumer)));
+
+        let mut preferred_feedbacks: Vec<_> = self
+            .preferred_feedbacks
+            .iter()
+            .map(|(producer, consumer)| (*producer.must_registered(), *consumer.must_registered()))
+            .collect();
+        preferred_feedbacks.sort_by_key(edge_key);
+
+        let mut tick_priorities: Vec<_> = self
+            .tick_priorities
+            .iter()
+            .map(|(node_index, priority)| (*node_index, *priority))
+            .collect();
+        tick_priorities.sort_by_key(|(node_index, _)| node_index.index());
+
+        let mut order_constraints: Vec<_> = self.order_constraints.iter().copied().collect();
+        order_constraints.sort_by_key(|(before, after)| (before.index(), after.index()));
+
+        let mut normals: Vec<_> = self
+            .normals
+            .iter()
+            .map(|(consumer, (producer, _))| (*consumer, *producer))
+            .collect();
+        normals.sort_by_key(|(consumer, producer)| {
+            (consumer.node_index().index(), producer.node_index().index())
+        });
+
+        Snapshot {
+            index_counter: self.graph.index_counter(),
+            nodes,
+            edges,
+            delayed_data,
+            preferred_feedbacks,
+            tick_priorities,
+            order_constraints,
+            normals,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Rebuild a graph stored through `Serialize`. Nodes keep their original
+/// indices.
+#[cfg(feature = "serde")]
+impl<'de, N, NI, CI, PI> serde::Deserialize<'de> for SignalGraph<N, NI, CI, PI>
+where
+    N: NodeWrapper<Class = NI::Class, Consumer = NI::Consumer, Producer = NI::Producer>
+        + serde::Deserialize<'de>,
+    N::Payload: serde::Deserialize<'de>,
+    NI: NodeIndex<ConsumerIndex = CI, ProducerIndex = PI> + serde::Deserialize<'de>,
+    CI: ConsumerIndex<NodeIndex = NI, Consumer = NI::Consumer> + serde::Deserialize<'de>,
+    PI: ProducerIndex<NodeIndex = NI, Producer = NI::Producer> + serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+
+        let snapshot = Snapshot::<N, NI, PI, CI, N::Payload>::deserialize(deserializer)?;
+
+        let mut graph = Self::new();
+        graph.deferred = true;
+        for (node_index, node) in snapshot.nodes {
+            if !graph.graph.restore_node(
+                SignalNodeIndex::Registered(node_index),
+                SignalNode::Registered(node),
+            ) {
+                return Err(D::Error::custom(format!(
+                    "duplicate node index {}",
+                    node_index.index()
+                )));
+            }
+        }
+        for (node_index, priority) in snapshot.tick_priorities {
+            graph.set_tick_priority(node_index, priority);
+        }
+        graph.order_constraints.extend(snapshot.order_constraints);
+        for (producer, consumer) in snapshot.edges {
+            graph
+                .add_edge(producer, consumer)
+                .map_err(|error| D::Error::custom(Error::from(error)))?;
+        }
+        for (producer, consumer) in snapshot.preferred_feedbacks {
+            graph.set_feedback_preference(producer, consumer, true);
+        }
+        for (consumer, producer) in snapshot.normals {
+            graph
+                .set_normalled_producer(consumer, producer)
+                .map_err(|error| D::Error::custom(Error::from(error)))?;
+        }
+        graph.deferred = false;
+        graph.refresh();
+
+        for (producer, consumer, data) in snapshot.delayed_data {
+            let edge = (
+                SignalProducerIndex::Registered(producer),
+                SignalConsumerIndex::Registered(consumer),
+            );
+            let source = match graph.feedback_edges.get(&edge) {
+                Some((source, _)) => *source,
+                None => {
+                    return Err(D::Error::custom(
+                        "delayed data do not match any feedback edge",
+                    ))
+                }
+            };
+            if let Some(source) = graph.graph.node_mut(&source) {
+                source.write(FeedbackSourceConsumer, data);
+            }
+        }
+        graph.graph.restore_index_counter(snapshot.index_counter);
+
+        Ok(graph)
+    }
+}
+
+impl<N> From<FeedbackSource<N::Payload>> for SignalNode<N>
+where
+    N: NodeWrapper,
+{
+    fn from(feedback_source: FeedbackSource<N::Payload>) -> Self {
+        Self::Internal(InternalNode::FeedbackSource(feedback_source))
+    }
+}
+
+impl<N> From<FeedbackSink<N::Payload>> for SignalNode<N>
+where
+    N: NodeWrapper,
+{
+    fn from(feedback_sink: FeedbackSink<N::Payload>) -> Self {
+        Self::Internal(InternalNode::FeedbackSink(feedback_sink))
+    }
+}
+
+impl<N> From<InputNode<N::Payload>> for SignalNode<N>
+where
+    N: NodeWrapper,
+{
+    fn from(input: InputNode<N::Payload>) -> Self {
+        Self::Internal(InternalNode::Input(input))
+    }
+}
+
+impl<N> From<OutputNode<N::Payload>> for SignalNode<N>
+where
+    N: NodeWrapper,
+{
+    fn from(output: OutputNode<N::Payload>) -> Self {
+        Self::Internal(InternalNode::Output(output))
+    }
+}
+
+impl<C> From<FeedbackSourceConsumer> for SignalConsumer<C>
+where
+    C: Hash + Copy,
+{
+    fn from(feedback_source: FeedbackSourceConsumer) -> Self {
+        Self::Internal(InternalConsumer::FeedbackSource(feedback_source))
+    }
+}
+
+impl<C> From<OutputNodeConsumer> for SignalConsumer<C>
+where
+    C: Hash + Copy,
+{
+    fn from(output: OutputNodeConsumer) -> Self {
+        Self::Internal(InternalConsumer::Output(output))
+    }
+}
+
+impl<P> From<InputNodeProducer> for SignalProducer<P>
+where
+    P: Hash + Copy,
+{
+    fn from(input: InputNodeProducer) -> Self {
+        Self::Internal(InternalProducer::Input(input))
+    }
+}
+
+impl<P> From<FeedbackSinkProducer> for SignalProducer<P>
+where
+    P: Hash + Copy,
+{
+    fn from(feedback_sink: FeedbackSinkProducer) -> Self {
+        Self::Internal(InternalProducer::FeedbackSink(feedback_sink))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::{self, FeedbackSinkProducer, FeedbackSourceConsumer};
+    use crate::node::{
+        CommonConsumerIndex, CommonProducerIndex, ExternalConsumer, ExternalNodeWrapper,
+        ExternalProducer, Node, NodeWrapper,
+    };
+    use crate::transform::{Offset, Scale};
+
+    type Payload = i32;
+
+    struct Generator(Payload);
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    enum GeneratorConsumer {}
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    struct GeneratorProducer;
+
+    impl Node<Payload> for Generator {
+        type Consumer = GeneratorConsumer;
+        type Producer = GeneratorProducer;
+
+        fn read(&self, _producer: Self::Producer) -> Payload {
+            self.0
+        }
+    }
+
+    impl From<Generator> for TestNode {
+        fn from(generator: Generator) -> Self {
+            TestNode::Generator(generator)
+        }
+    }
+
+    impl From<GeneratorProducer> for TestProducer {
+        fn from(generator: GeneratorProducer) -> Self {
+            TestProducer::Generator(generator)
+        }
+    }
+
+    #[derive(Default)]
+    struct Sum {
+        input1: Payload,
+        input2: Payload,
+        output: Payload,
+    }
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    enum SumConsumer {
+        In1,
+        In2,
+    }
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    struct SumProducer;
+
+    impl Node<Payload> for Sum {
+        type Consumer = SumConsumer;
+        type Producer = SumProducer;
+
+        fn tick(&mut self) {
+            self.output = self.input1 + self.input2;
+        }
+
+        fn read(&self, _producer: Self::Producer) -> Payload {
+            self.output
+        }
+
+        fn write(&mut self, consumer: Self::Consumer, input: Payload) {
+            match consumer {
+                Self::Consumer::In1 => self.input1 = input,
+                Self::Consumer::In2 => self.input2 = input,
+            }
+        }
+
+        fn consumer_type(_consumer: Self::Consumer) -> Option<PayloadType> {
+            Some(PayloadType::of::<Payload>())
+        }
+
+        fn producer_type(_producer: Self::Producer) -> Option<PayloadType> {
+            Some(PayloadType::of::<Payload>())
+        }
+    }
+
+    impl From<Sum> for TestNode {
+        fn from(sum: Sum) -> Self {
+            TestNode::Sum(sum)
+        }
+    }
+
+    impl From<SumConsumer> for TestConsumer {
+        fn from(sum: SumConsumer) -> Self {
+            TestConsumer::Sum(sum)
+        }
+    }
+
+    impl From<SumProducer> for TestProducer {
+        fn from(sum: SumProducer) -> Self {
+            TestProducer::Sum(sum)
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder(Payload);
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    struct RecorderConsumer;
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    struct RecorderProducer;
+
+    impl Node<Payload> for Recorder {
+        type Consumer = RecorderConsumer;
+        type Producer = RecorderProducer;
+
+        fn reset(&mut self) {
+            self.0 = Payload::default();
+        }
+
+        fn read(&self, _producer: Self::Producer) -> Payload {
+            self.0
+        }
+
+        fn write(&mut self, _consumer: Self::Consumer, input: Payload) {
+            self.0 = input;
+        }
+    }
+
+    impl From<Recorder> for TestNode {
+        fn from(recorder: Recorder) -> Self {
+            TestNode::Recorder(recorder)
+        }
+    }
+
+    impl From<RecorderConsumer> for TestConsumer {
+        fn from(recorder: RecorderConsumer) -> Self {
+            TestConsumer::Recorder(recorder)
+        }
+    }
+
+    impl From<RecorderProducer> for TestProducer {
+        fn from(recorder: RecorderProducer) -> Self {
+            TestProducer::Recorder(recorder)
+        }
+    }
+
+    struct Gate(bool);
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    enum GateConsumer {}
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    struct GateProducer;
+
+    impl Node<Payload> for Gate {
+        type Consumer = GateConsumer;
+        type Producer = GateProducer;
+
+        fn read(&self, _producer: Self::Producer) -> Payload {
+            self.0 as Payload
+        }
+
+        fn producer_type(_producer: Self::Producer) -> Option<PayloadType> {
+            Some(PayloadType::of::<bool>())
+        }
+    }
+
+    impl From<Gate> for TestNode {
+        fn from(gate: Gate) -> Self {
+            TestNode::Gate(gate)
+        }
+    }
+
+    impl From<GateProducer> for TestProducer {
+        fn from(gate: GateProducer) -> Self {
+            TestProducer::Gate(gate)
+        }
+    }
+
+    enum TestNode {
+        Generator(Generator),
+        Sum(Sum),
+        Recorder(Recorder),
+        Gate(Gate),
+    }
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    enum TestNodeClass {
+        Generator,
+        Sum,
+        Recorder,
+        Gate,
+    }
+
+    impl NodeClass for TestNode {
+        type Class = TestNodeClass;
+
+        fn class(&self) -> Self::Class {
+            match self {
+                Self::Generator(_) => TestNodeClass::Generator,
+                Self::Sum(_) => TestNodeClass::Sum,
+                Self::Recorder(_) => TestNodeClass::Recorder,
+                Self::Gate(_) => TestNodeClass::Gate,
+            }
+        }
+    }
+
+    impl NodeWrapper for TestNode {
+        type Payload = Payload;
+        type Consumer = TestConsumer;
+        type Producer = TestProducer;
+
+        fn tick(&mut self) {
+            match self {
+                Self::Generator(generator) => generator.tick(),
+                Self::Sum(sum) => sum.tick(),
+                Self::Recorder(recorder) => recorder.tick(),
+                Self::Gate(gate) => gate.tick(),
+            }
+        }
+
+        fn reset(&mut self) {
+            match self {
+                Self::Generator(generator) => generator.reset(),
+                Self::Sum(sum) => sum.reset(),
+                Self::Recorder(recorder) => recorder.reset(),
+                Self::Gate(gate) => gate.reset(),
+            }
+        }
+
+        fn read<IntoP>(&self, producer: IntoP) -> Payload
+        where
+            IntoP: Into<Self::Producer>,
+        {
+            let producer = producer.into();
+            match self {
+                Self::Generator(generator) => match producer {
+                    Self::Producer::Generator(producer) => generator.read(producer),
+                    _ => panic!("Node does not offer such producer"),
+                },
+                Self::Sum(sum) => match producer {
+                    Self::Producer::Sum(producer) => sum.read(producer),
+                    _ => panic!("Node does not offer such producer"),
+                },
+                Self::Recorder(recorder) => match producer {
+                    Self::Producer::Recorder(producer) => recorder.read(producer),
+                    _ => panic!("Node does not offer such producer"),
+                },
+                Self::Gate(gate) => match producer {
+                    Self::Producer::Gate(producer) => gate.read(producer),
+                    _ => panic!("Node does not offer such producer"),
+                },
+            }
+        }
+
+        fn write<IntoC>(&mut self, consumer: IntoC, input: Payload)
+        where
+            IntoC: Into<Self::Consumer>,
+        {
+            let consumer = consumer.into();
+            match self {
+                Self::Generator(_) => panic!("Node does not offer such consumer"),
+                Self::Sum(sum) => match consumer {
+                    Self::Consumer::Sum(consumer) => sum.write(consumer, input),
+                    _ => panic!("Node does not offer such consumer"),
+                },
+                Self::Recorder(recorder) => match consumer {
+                    Self::Consumer::Recorder(consumer) => recorder.write(consumer, input),
+                    _ => panic!("Node does not offer such consumer"),
+                },
+                Self::Gate(_) => panic!("Node does not offer such consumer"),
+            }
+        }
+
+        fn consumer_type<IntoC>(&self, consumer: IntoC) -> Option<PayloadType>
+        where
+            IntoC: Into<Self::Consumer>,
+        {
+            match consumer.into() {
+                Self::Consumer::Sum(consumer) => Sum::consumer_type(consumer),
+                Self::Consumer::Recorder(consumer) => Recorder::consumer_type(consumer),
+            }
+        }
+
+        fn producer_type<IntoP>(&self, producer: IntoP) -> Option<PayloadType>
+        where
+            IntoP: Into<Self::Producer>,
+        {
+            match producer.into() {
+                Self::Producer::Generator(producer) => Generator::producer_type(producer),
+                Self::Producer::Sum(producer) => Sum::producer_type(producer),
+                Self::Producer::Recorder(producer) => Recorder::producer_type(producer),
+                Self::Producer::Gate(producer) => Gate::producer_type(producer),
+            }
+        }
+    }
+
+    impl ExternalNodeWrapper<Payload> for TestNode {}
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    struct TestNodeIndex {
+        class: TestNodeClass,
+        index: usize,
+    }
+
+    impl NodeIndex for TestNodeIndex {
+        type Class = TestNodeClass;
+        type Consumer = TestConsumer;
+        type ConsumerIndex = TestConsumerIndex;
+        type Producer = TestProducer;
+        type ProducerIndex = TestProducerIndex;
+
+        fn new(class: TestNodeClass, index: usize) -> Self {
+            Self { class, index }
+        }
+
+        fn index(&self) -> usize {
+            self.index
+        }
+
+        fn try_consumer<IntoC>(&self, consumer: IntoC) -> Result<Self::ConsumerIndex, Error>
+        where
+            IntoC: Into<TestConsumer>,
+        {
+            let consumer = consumer.into();
+            match self.class {
+                Self::Class::Generator => Err(Error::InvalidPort),
+                Self::Class::Sum => match consumer {
+                    Self::Consumer::Sum(_) => Ok(Self::ConsumerIndex::new(*self, consumer)),
+                    _ => Err(Error::InvalidPort),
+                },
+                Self::Class::Recorder => match consumer {
+                    Self::Consumer::Recorder(_) => Ok(Self::ConsumerIndex::new(*self, consumer)),
+                    _ => Err(Error::InvalidPort),
+                },
+                Self::Class::Gate => Err(Error::InvalidPort),
+            }
+        }
+
+        fn try_producer<IntoP>(&self, producer: IntoP) -> Result<Self::ProducerIndex, Error>
+        where
+            IntoP: Into<TestProducer>,
+        {
+            let producer = producer.into();
+            match self.class {
+                Self::Class::Generator => match producer {
+                    Self::Producer::Generator(_) => Ok(Self::ProducerIndex::new(*self, producer)),
+                    _ => Err(Error::InvalidPort),
+                },
+                Self::Class::Sum => match producer {
+                    Self::Producer::Sum(_) => Ok(Self::ProducerIndex::new(*self, producer)),
+                    _ => Err(Error::InvalidPort),
+                },
+                Self::Class::Recorder => match producer {
+                    Self::Producer::Recorder(_) => Ok(Self::ProducerIndex::new(*self, producer)),
+                    _ => Err(Error::InvalidPort),
+                },
+                Self::Class::Gate => match producer {
+                    Self::Producer::Gate(_) => Ok(Self::ProducerIndex::new(*self, producer)),
+                    _ => Err(Error::InvalidPort),
+                },
+            }
+        }
+    }
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    enum TestConsumer {
+        Sum(SumConsumer),
+        Recorder(RecorderConsumer),
+    }
+
+    impl ExternalConsumer for TestConsumer {}
+
+    type TestConsumerIndex = CommonConsumerIndex<TestNodeIndex>;
+
+    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
+    enum TestProducer {
+        Generator(GeneratorProducer),
+        Sum(SumProducer),
+        Recorder(RecorderProducer),
+        Gate(GateProducer),
+    }
+
+    impl ExternalProducer for TestProducer {}
+
+    type TestProducerIndex = CommonProducerIndex<TestNodeIndex>;
+
+    type TestSignalGraph =
+        SignalGraph<TestNode, TestNodeIndex, TestConsumerIndex, TestProducerIndex>;
+
+    #[test]
+    fn write_tick_read_internal_signal_node() {
+        let (source, sink) = feedback::new_feedback_pair();
+        let mut source: SignalNode<TestNode> = source.into();
+        let mut sink: SignalNode<TestNode> = sink.into();
+
+        source.write(FeedbackSourceConsumer, 10);
+        source.tick();
+        sink.tick();
+        assert_eq!(sink.read(FeedbackSinkProducer), 10);
+    }
+
+    #[test]
+    fn write_tick_read_registered_signal_node() {
+        let mut node: SignalNode<TestNode> = SignalNode::Registered(Sum::default().into());
+
+        node.write(SignalConsumer::Registered(SumConsumer::In1.into()), 10);
+        node.write(SignalConsumer::Registered(SumConsumer::In2.into()), 20);
+        node.tick();
+        assert_eq!(
+            node.read(SignalProducer::Registered(SumProducer.into())),
+            30
+        );
+    }
+
+    #[test]
+    fn try_read_and_write() {
+        let mut graph = TestSignalGraph::new();
+        let sum = graph.add_node(Sum::default());
+
+        graph.try_write(sum.consumer(SumConsumer::In1), 10).unwrap();
+        graph.try_write(sum.consumer(SumConsumer::In2), 20).unwrap();
+        graph.tick();
+        assert_eq!(graph.try_read(sum.producer(SumProducer)).unwrap(), 30);
+
+        graph.remove_node(sum);
+        assert!(matches!(
+            graph.try_read(sum.producer(SumProducer)),
+            Err(Error::StaleIndex)
+        ));
+        assert!(matches!(
+            graph.try_write(sum.consumer(SumConsumer::In1), 10),
+            Err(Error::StaleIndex)
+        ));
+    }
+
+    //
+    //    [Rec]
+    //      |
+    //     [+]
+    //    /   \
+    //  [1]   [2]
+    //
+    #[test]
+    fn tick_in_simple_tree() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let two = graph.add_node(Generator(2));
+        let sum = graph.add_node(Sum::default());
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(
+            two.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In2),
+        );
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 3);
+    }
+
+    //
+    //  [Rec]   [Rec]
+    //      \   /
+    //       [+]
+    //      /   \
+    //    [1]   [2]
+    //
+    #[test]
+    fn tick_with_multiple_consumers() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let two = graph.add_node(Generator(2));
+        let sum = graph.add_node(Sum::default());
+        let recorder1 = graph.add_node(Recorder::default());
+        let recorder2 = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(
+            two.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In2),
+        );
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder1.consumer(RecorderConsumer),
+        );
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder2.consumer(RecorderConsumer),
+        );
+
+        graph.tick();
+        assert_eq!(graph.node(&recorder1).unwrap().read(RecorderProducer), 3);
+        assert_eq!(graph.node(&recorder2).unwrap().read(RecorderProducer), 3);
+    }
+
+    //
+    //  [Rec]    __
+    //      \   /  |
+    //       [+]   V
+    //      /   \__|
+    //    [1]
+    //
+    #[test]
+    fn tick_with_internal_cycle() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 2);
+    }
+
+    #[test]
+    fn reset_nodes_and_feedbacks() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+        graph.tick();
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 2);
+
+        graph.reset();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 0);
+
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
+    }
+
+    //
+    //    [Rec]
+    //      |
+    //     [1]
+    //
+    #[test]
+    fn check_for_edge() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+
+        assert!(graph.has_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        ));
+    }
+
+    //
+    //    [Rec]
+    //      |
+    //     [1]
+    //
+    #[test]
+    fn remove_edge() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+
+        graph.remove_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+
+        assert!(!graph.has_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        ));
+    }
+
+    //           __
+    //          /  |
+    //       [+]   V
+    //      /   \__|
+    //    [1]
+    //
+    #[test]
+    fn check_for_feedback_edge() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
+
+        assert!(graph.has_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2),));
+        assert!(!graph.feedback_edges.is_empty());
+    }
+
+    //           __
+    //          /  |
+    //       [+]   V
+    //      /   \__|
+    //    [1]
+    //
+    #[test]
+    fn remove_feedback_edge() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
+
+        graph.remove_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
+
+        assert!(!graph.has_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2),));
+        assert!(graph.feedback_edges.is_empty());
+    }
+
+    //           ___
+    //          /   |
+    //       [Rec]  |
+    // del ->  |    |
+    //       [Rec]  V
+    //         |    |
+    //        [+]   |
+    //       /   \__|
+    //      [1]
+    //
+    #[test]
+    fn remove_redundant_feedback_edge() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        let recorder1 = graph.add_node(Recorder::default());
+        let recorder2 = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder1.consumer(RecorderConsumer),
+        );
+        graph.must_add_edge(
+            recorder1.producer(RecorderProducer),
+            recorder2.consumer(RecorderConsumer),
+        );
+        graph.must_add_edge(
+            recorder2.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In2),
+        );
+
+        assert!(!graph.feedback_edges.is_empty());
+
+        graph.remove_edge(
+            recorder1.producer(RecorderProducer),
+            recorder2.consumer(RecorderConsumer),
+        );
+
+        assert!(graph.feedback_edges.is_empty());
+    }
+
+    //             ___
+    //            /   |
+    //         [Rec]  |
+    //           |    |
+    //         [Rec]  V
+    //           |    |
+    //          [+]   |
+    //  del -> /   \__|
+    //        [1]
+    //
+    #[test]
+    fn do_not_remove_needed_feedback_edge() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        let recorder1 = graph.add_node(Recorder::default());
+        let recorder2 = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder1.consumer(RecorderConsumer),
+        );
+        graph.must_add_edge(
+            recorder1.producer(RecorderProducer),
+            recorder2.consumer(RecorderConsumer),
+        );
+        graph.must_add_edge(
+            recorder2.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In2),
+        );
+        let original_feedbacks = graph.feedback_edges.len();
+
+        graph.remove_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        assert_eq!(graph.feedback_edges.len(), original_feedbacks);
+    }
+
+    //          ___
+    //         /   |
+    //      [Rec]  |
+    //        |    V
+    //       [+]   |
+    //      /   \__|
+    //    [1]
+    //
+    #[test]
+    fn place_feedback_independently_of_edge_order() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            recorder.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In2),
+        );
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+
+        assert!(graph.is_feedback_edge(
+            recorder.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In2),
+        ));
+        assert!(!graph.is_feedback_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        ));
+    }
+
+    //          ___
+    //         /   |
+    //      [Rec]  |
+    //        |    V
+    //       [+]   |
+    //      /   \__|
+    //    [1]
+    //
+    #[test]
+    fn delay_preferred_feedback_edge() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+        graph.set_feedback_preference(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+            true,
+        );
+        graph.must_add_edge(
+            recorder.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In2),
+        );
+
+        assert!(graph.is_feedback_preferred(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        ));
+        assert!(graph.is_feedback_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        ));
+        assert!(!graph.is_feedback_edge(
+            recorder.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In2),
+        ));
+
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 0);
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
+
+        graph.set_feedback_preference(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+            false,
+        );
+
+        assert!(!graph.is_feedback_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        ));
+        assert!(graph.is_feedback_edge(
+            recorder.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In2),
+        ));
+    }
+
+    //
+    //  [Rec]    __
+    //      \   /  |
+    //       [+]   V
+    //      /   \__|
+    //    [1]
+    //
+    #[test]
+    fn list_feedback_edges() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+
+        let feedback_edges: Vec<_> = graph.feedback_edges().collect();
+        assert_eq!(feedback_edges.len(), 1);
+        assert!(feedback_edges[0] == (sum.producer(SumProducer), sum.consumer(SumConsumer::In2)));
+    }
+
+    //          ___
+    //         /   |
+    //      [Rec]  |
+    //        |    V
+    //       [+]   |
+    //      /   \__|
+    //    [1]
+    //
+    #[test]
+    fn report_feedback_changes() {
+        let mut graph = TestSignalGraph::new();
+        graph.enable_event_queue();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+        graph.must_add_edge(
+            recorder.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In2),
+        );
+
+        let changes: Vec<_> = drain_feedback_changes(&mut graph);
+        assert!(
+            changes
+                == [Event::EdgeDelayed(
+                    recorder.producer(RecorderProducer),
+                    sum.consumer(SumConsumer::In2),
+                )]
+        );
+
+        graph.set_feedback_preference(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+            true,
+        );
+
+        let changes: Vec<_> = drain_feedback_changes(&mut graph);
+        assert_eq!(changes.len(), 2);
+        assert!(changes.contains(&Event::EdgeDirect(
+            recorder.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In2),
+        )));
+        assert!(changes.contains(&Event::EdgeDelayed(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        )));
+        assert_eq!(graph.drain_events().count(), 0);
+    }
+
+    fn drain_feedback_changes(
+        graph: &mut TestSignalGraph,
+    ) -> Vec<Event<TestNodeIndex, TestProducerIndex, TestConsumerIndex>> {
+        graph
+            .drain_events()
+            .filter(|event| matches!(event, Event::EdgeDelayed(..) | Event::EdgeDirect(..)))
+            .collect()
+    }
+
+    #[test]
+    fn report_topology_changes() {
+        use alloc::rc::Rc;
+        use core::cell::RefCell;
+
+        let mut graph = TestSignalGraph::new();
+        let observed = Rc::new(RefCell::new(Vec::new()));
+        let observer_events = Rc::clone(&observed);
+        graph.add_observer(move |event| observer_events.borrow_mut().push(*event));
+        graph.enable_event_queue();
+
+        let one = graph.add_node(Generator(1));
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+        graph.remove_node(one);
+
+        let events: Vec<_> = graph.drain_events().collect();
+        assert_eq!(events, *observed.borrow());
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| **event != Event::OrderRecomputed)
+                .copied()
+                .collect::<Vec<_>>(),
+            [
+                Event::NodeAdded(one),
+                Event::NodeAdded(recorder),
+                Event::EdgeAdded(
+                    one.producer(GeneratorProducer),
+                    recorder.consumer(RecorderConsumer)
+                ),
+                Event::EdgeRemoved(
+                    one.producer(GeneratorProducer),
+                    recorder.consumer(RecorderConsumer)
+                ),
+                Event::NodeRemoved(one),
+            ]
+        );
+        assert!(events.contains(&Event::OrderRecomputed));
+    }
+
+    #[test]
+    fn queue_events_only_when_enabled() {
+        let mut graph = TestSignalGraph::new();
+        graph.add_node(Generator(1));
+        assert_eq!(graph.drain_events().count(), 0);
+
+        graph.enable_event_queue();
+        let two = graph.add_node(Generator(2));
+        graph.remove_node(two);
+        graph.disable_event_queue();
+        assert_eq!(graph.drain_events().count(), 0);
+
+        graph.enable_event_queue();
+        graph.add_node(Generator(3));
+        assert!(graph.drain_events().count() > 0);
+    }
+
+    //
+    //    [Rec]
+    //      | *2
+    //     [1]
+    //
+    #[test]
+    fn tick_with_edge_transform() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+
+        graph.set_edge_transform(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+            Scale(2),
+        );
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 2);
+
+        graph.set_edge_transform(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+            |input| input - 10,
+        );
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), -9);
+
+        graph.remove_edge_transform(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
+    }
+
+    #[test]
+    fn keep_transforms_apart_when_reusing_their_slots() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let first = graph.add_node(Recorder::default());
+        let second = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            first.consumer(RecorderConsumer),
+        );
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            second.consumer(RecorderConsumer),
+        );
+
+        graph.set_edge_transform(
+            one.producer(GeneratorProducer),
+            first.consumer(RecorderConsumer),
+            Scale(2),
+        );
+        graph.remove_edge_transform(
+            one.producer(GeneratorProducer),
+            first.consumer(RecorderConsumer),
+        );
+        graph.set_edge_transform(
+            one.producer(GeneratorProducer),
+            second.consumer(RecorderConsumer),
+            Offset(10),
+        );
+
+        graph.tick();
+        assert_eq!(graph.node(&first).unwrap().read(RecorderProducer), 1);
+        assert_eq!(graph.node(&second).unwrap().read(RecorderProducer), 11);
+    }
+
+    //
+    //  [Rec]    __
+    //      \   /  |
+    //       [+]   V +1
+    //      /   \__|
+    //    [1]
+    //
+    #[test]
+    fn tick_with_feedback_edge_transform() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+        graph.set_edge_transform(
+            sum.producer(SumProducer),
+            sum.consumer(SumConsumer::In2),
+            Offset(1),
+        );
+
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 2);
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 4);
+    }
+
+    #[test]
+    fn drop_edge_transform_with_edge() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+        graph.set_edge_transform(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+            Scale(2),
+        );
+
+        graph.remove_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+
+        assert!(!graph.has_edge_transform(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        ));
+    }
+
+    #[test]
+    fn fall_back_to_normalled_producer() {
+        let mut graph = TestSignalGraph::new();
+        let recorder = graph.add_node(Recorder::default());
+        let one = graph.add_node(Generator(1));
+        let five = graph.add_node(Generator(5));
+        graph
+            .set_normalled_producer(
+                recorder.consumer(RecorderConsumer),
+                five.producer(GeneratorProducer),
+            )
+            .unwrap();
+        assert!(!graph.has_edge(
+            five.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer)
+        ));
+
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 5);
+
+        assert!(graph
+            .add_edge(
+                one.producer(GeneratorProducer),
+                recorder.consumer(RecorderConsumer)
+            )
+            .is_ok());
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
+
+        graph.remove_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 5);
+
+        graph.remove_node(five);
+        assert_eq!(
+            graph.normalled_producer(recorder.consumer(RecorderConsumer)),
+            None
+        );
     }
 
-    #[derive(PartialEq, Eq, Copy, Clone, Hash, Debug)]
-    enum TestConsumer {
-        Sum(SumConsumer),
-        Recorder(RecorderConsumer),
+    #[test]
+    fn delay_normalled_producer_closing_cycle() {
+        let mut graph = TestSignalGraph::new();
+        let recorder = graph.add_node(Recorder::default());
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        graph.must_add_edge(
+            one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph
+            .set_normalled_producer(sum.consumer(SumConsumer::In2), sum.producer(SumProducer))
+            .unwrap();
+        graph
+            .set_normalled_producer(
+                recorder.consumer(RecorderConsumer),
+                sum.producer(SumProducer),
+            )
+            .unwrap();
+
+        graph.tick();
+        assert_eq!(graph.node(&sum).unwrap().read(SumProducer), 1);
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
+        graph.tick();
+        assert_eq!(graph.node(&sum).unwrap().read(SumProducer), 2);
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 2);
     }
 
-    impl ExternalConsumer for TestConsumer {}
+    #[test]
+    fn transform_normalled_producer() {
+        let mut graph = TestSignalGraph::new();
+        let recorder = graph.add_node(Recorder::default());
+        let five = graph.add_node(Generator(5));
+        graph
+            .set_normalled_producer(
+                recorder.consumer(RecorderConsumer),
+                five.producer(GeneratorProducer),
+            )
+            .unwrap();
+        graph.set_normal_transform(recorder.consumer(RecorderConsumer), Scale(2));
+        assert!(graph.has_normal_transform(recorder.consumer(RecorderConsumer)));
 
-    type TestConsumerIndex = CommonConsumerIndex<TestNodeIndex>;
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 10);
 
-    #[derive(PartialEq, Eq, Copy, Clone, Hash)]
-    enum TestProducer {
-        Generator(GeneratorProducer),
-        Sum(SumProducer),
-        Recorder(RecorderProducer),
+        graph.remove_normalled_producer(recorder.consumer(RecorderConsumer));
+        assert!(!graph.has_normal_transform(recorder.consumer(RecorderConsumer)));
     }
 
-    impl ExternalProducer for TestProducer {}
+    #[test]
+    fn undo_and_report_normalled_producer_changes() {
+        let mut graph = TestSignalGraph::new();
+        graph.enable_history();
+        graph.enable_event_queue();
+        let recorder = graph.add_node(Recorder::default());
+        let one = graph.add_node(Generator(1));
+        let five = graph.add_node(Generator(5));
+        graph.drain_events().for_each(drop);
 
-    type TestProducerIndex = CommonProducerIndex<TestNodeIndex>;
+        graph
+            .set_normalled_producer(
+                recorder.consumer(RecorderConsumer),
+                one.producer(GeneratorProducer),
+            )
+            .unwrap();
+        graph
+            .set_normalled_producer(
+                recorder.consumer(RecorderConsumer),
+                five.producer(GeneratorProducer),
+            )
+            .unwrap();
+        graph.set_normal_transform(recorder.consumer(RecorderConsumer), Scale(2));
+        graph.remove_normalled_producer(recorder.consumer(RecorderConsumer));
+        let events: Vec<_> = graph
+            .drain_events()
+            .filter(|event| !matches!(event, Event::OrderRecomputed))
+            .collect();
+        assert_eq!(
+            events,
+            [
+                Event::NormalAdded(
+                    one.producer(GeneratorProducer),
+                    recorder.consumer(RecorderConsumer)
+                ),
+                Event::NormalRemoved(
+                    one.producer(GeneratorProducer),
+                    recorder.consumer(RecorderConsumer)
+                ),
+                Event::NormalAdded(
+                    five.producer(GeneratorProducer),
+                    recorder.consumer(RecorderConsumer)
+                ),
+                Event::NormalRemoved(
+                    five.producer(GeneratorProducer),
+                    recorder.consumer(RecorderConsumer)
+                ),
+            ]
+        );
 
-    type TestSignalGraph =
-        SignalGraph<TestNode, TestNodeIndex, TestConsumerIndex, TestProducerIndex>;
+        assert!(graph.undo());
+        assert_eq!(
+            graph.normalled_producer(recorder.consumer(RecorderConsumer)),
+            Some(five.producer(GeneratorProducer))
+        );
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 10);
 
-    #[test]
-    fn write_tick_read_internal_signal_node() {
-        let (source, sink) = feedback::new_feedback_pair();
-        let mut source: SignalNode<TestNode> = source.into();
-        let mut sink: SignalNode<TestNode> = sink.into();
+        assert!(graph.undo());
+        assert_eq!(
+            graph.normalled_producer(recorder.consumer(RecorderConsumer)),
+            Some(one.producer(GeneratorProducer))
+        );
+        assert!(graph.undo());
+        assert_eq!(
+            graph.normalled_producer(recorder.consumer(RecorderConsumer)),
+            None
+        );
 
-        source.write(FeedbackSourceConsumer, 10);
-        source.tick();
-        sink.tick();
-        assert_eq!(sink.read(FeedbackSinkProducer), 10);
+        assert!(graph.redo());
+        assert!(graph.redo());
+        assert_eq!(
+            graph.normalled_producer(recorder.consumer(RecorderConsumer)),
+            Some(five.producer(GeneratorProducer))
+        );
     }
 
     #[test]
-    fn write_tick_read_registered_signal_node() {
-        let mut node: SignalNode<TestNode> = SignalNode::Registered(Sum::default().into());
+    fn apply_disconnect_policy() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let recorder = graph.add_node(Recorder::default());
+        let connect = |graph: &mut TestSignalGraph| {
+            graph.must_add_edge(
+                one.producer(GeneratorProducer),
+                recorder.consumer(RecorderConsumer),
+            );
+            graph.tick();
+        };
+        let disconnect = |graph: &mut TestSignalGraph| {
+            graph.remove_edge(
+                one.producer(GeneratorProducer),
+                recorder.consumer(RecorderConsumer),
+            );
+        };
+
+        connect(&mut graph);
+        disconnect(&mut graph);
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
 
-        node.write(SignalConsumer::Registered(SumConsumer::In1.into()), 10);
-        node.write(SignalConsumer::Registered(SumConsumer::In2.into()), 20);
-        node.tick();
+        graph.set_disconnect_policy(recorder.consumer(RecorderConsumer), DisconnectPolicy::Reset);
+        connect(&mut graph);
+        disconnect(&mut graph);
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 0);
+
+        graph.set_disconnect_policy(
+            recorder.consumer(RecorderConsumer),
+            DisconnectPolicy::Value(7),
+        );
+        connect(&mut graph);
+        graph.remove_node(one);
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 7);
+
+        graph.remove_disconnect_policy(recorder.consumer(RecorderConsumer));
         assert_eq!(
-            node.read(SignalProducer::Registered(SumProducer.into())),
-            30
+            graph.disconnect_policy(recorder.consumer(RecorderConsumer)),
+            DisconnectPolicy::Keep
         );
     }
 
+    //
+    //     [+]
+    //      |
+    //    [Gate]
+    //
+    #[test]
+    fn return_error_on_add_edge_with_incompatible_types() {
+        let mut graph = TestSignalGraph::new();
+        let gate = graph.add_node(Gate(true));
+        let sum = graph.add_node(Sum::default());
+
+        assert!(matches!(
+            graph.add_edge(gate.producer(GateProducer), sum.consumer(SumConsumer::In1)),
+            Err(AddEdgeError::IncompatibleTypes { .. })
+        ));
+        assert!(!graph.has_edge(gate.producer(GateProducer), sum.consumer(SumConsumer::In1)));
+    }
+
     //
     //    [Rec]
     //      |
-    //     [+]
-    //    /   \
-    //  [1]   [2]
+    //     [1]   [2]
     //
     #[test]
-    fn tick_in_simple_tree() {
+    fn return_error_on_add_edge_to_occupied_consumer() {
         let mut graph = TestSignalGraph::new();
         let one = graph.add_node(Generator(1));
         let two = graph.add_node(Generator(2));
-        let sum = graph.add_node(Sum::default());
         let recorder = graph.add_node(Recorder::default());
         graph.must_add_edge(
             one.producer(GeneratorProducer),
-            sum.consumer(SumConsumer::In1),
+            recorder.consumer(RecorderConsumer),
         );
-        graph.must_add_edge(
-            two.producer(GeneratorProducer),
-            sum.consumer(SumConsumer::In2),
+
+        let error = graph
+            .add_edge(
+                two.producer(GeneratorProducer),
+                recorder.consumer(RecorderConsumer),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            error,
+            AddEdgeError::OccupiedConsumer {
+                producer: two.producer(GeneratorProducer),
+                consumer: recorder.consumer(RecorderConsumer),
+                existing: one.producer(GeneratorProducer),
+            }
         );
+        assert_eq!(error.producer(), two.producer(GeneratorProducer));
+        assert!(alloc::format!("{}", error).contains("already fed by"));
+        assert_eq!(
+            Error::from(error),
+            Error::AddEdgeError(AddEdgeErrorKind::OccupiedConsumer)
+        );
+    }
+
+    //
+    //    [Rec]
+    //     | ^
+    //     v |
+    //     [+]
+    //
+    #[test]
+    fn return_error_on_add_edge_to_consumer_occupied_by_feedback() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        let recorder = graph.add_node(Recorder::default());
         graph.must_add_edge(
             sum.producer(SumProducer),
             recorder.consumer(RecorderConsumer),
         );
+        graph.must_add_edge(
+            recorder.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        assert!(graph.is_feedback_edge(
+            recorder.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In1)
+        ));
+
+        assert!(matches!(
+            graph.add_edge(
+                one.producer(GeneratorProducer),
+                sum.consumer(SumConsumer::In1)
+            ),
+            Err(AddEdgeError::OccupiedConsumer { existing, .. })
+                if existing == recorder.producer(RecorderProducer)
+        ));
+    }
+
+    //
+    //    [Rec]
+    //      |
+    //     [1]
+    //
+    #[test]
+    fn return_error_on_add_edge_to_removed_node() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let recorder = graph.add_node(Recorder::default());
+        graph.remove_node(recorder);
+
+        assert!(matches!(
+            graph.add_edge(
+                one.producer(GeneratorProducer),
+                recorder.consumer(RecorderConsumer)
+            ),
+            Err(AddEdgeError::NodeNotFound { .. })
+        ));
+        assert!(!graph.has_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer)
+        ));
 
         graph.tick();
-        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 3);
     }
 
     //
-    //  [Rec]   [Rec]
-    //      \   /
+    //      (out)
+    //        |
     //       [+]
     //      /   \
-    //    [1]   [2]
+    //   (in)   [2]
     //
     #[test]
-    fn tick_with_multiple_consumers() {
+    fn pass_data_through_graph_inputs_and_outputs() {
         let mut graph = TestSignalGraph::new();
-        let one = graph.add_node(Generator(1));
+        let input = graph.add_input("in", None).unwrap();
+        let output = graph.add_output("out", None).unwrap();
         let two = graph.add_node(Generator(2));
         let sum = graph.add_node(Sum::default());
-        let recorder1 = graph.add_node(Recorder::default());
-        let recorder2 = graph.add_node(Recorder::default());
-        graph.must_add_edge(
-            one.producer(GeneratorProducer),
-            sum.consumer(SumConsumer::In1),
-        );
+        graph
+            .connect_input(input, sum.consumer(SumConsumer::In1))
+            .unwrap();
         graph.must_add_edge(
             two.producer(GeneratorProducer),
             sum.consumer(SumConsumer::In2),
         );
+        graph
+            .connect_output(sum.producer(SumProducer), output)
+            .unwrap();
+        assert_eq!(graph.find_input("in"), Some(input));
+        assert_eq!(graph.find_output("out"), Some(output));
+
+        graph.set_input(input, 10).unwrap();
+        graph.tick();
+        assert_eq!(graph.output(output), Ok(12));
+        graph.tick();
+        assert_eq!(graph.output(output), Ok(12));
+
+        graph.set_input(input, 20).unwrap();
+        graph.tick();
+        assert_eq!(graph.output(output), Ok(22));
+
+        graph.disconnect_input(input, sum.consumer(SumConsumer::In1));
         graph.must_add_edge(
-            sum.producer(SumProducer),
-            recorder1.consumer(RecorderConsumer),
+            two.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.tick();
+        assert_eq!(graph.output(output), Ok(4));
+
+        graph.remove_output(output);
+        assert_eq!(graph.output(output), Err(Error::StaleIndex));
+        graph.tick();
+    }
+
+    #[test]
+    fn return_error_on_duplicate_graph_input_and_output_names() {
+        let mut graph = TestSignalGraph::new();
+        graph.add_input("in", None).unwrap();
+        graph.add_output("out", None).unwrap();
+
+        assert_eq!(graph.add_input("in", None), Err(Error::DuplicateName));
+        assert_eq!(graph.add_output("out", None), Err(Error::DuplicateName));
+        assert!(graph.add_input("out", None).is_ok());
+    }
+
+    //
+    //    (out)    [+]
+    //      |       |
+    //    [Gate]  (in)
+    //
+    #[test]
+    fn check_types_of_graph_inputs_and_outputs() {
+        let mut graph = TestSignalGraph::new();
+        let input = graph
+            .add_input("in", Some(PayloadType::of::<bool>()))
+            .unwrap();
+        let output = graph
+            .add_output("out", Some(PayloadType::of::<Payload>()))
+            .unwrap();
+        let gate = graph.add_node(Gate(true));
+        let sum = graph.add_node(Sum::default());
+
+        assert_eq!(
+            graph.connect_input(input, sum.consumer(SumConsumer::In1)),
+            Err(Error::AddEdgeError(AddEdgeErrorKind::IncompatibleTypes))
+        );
+        assert_eq!(
+            graph.connect_output(gate.producer(GateProducer), output),
+            Err(Error::AddEdgeError(AddEdgeErrorKind::IncompatibleTypes))
+        );
+
+        graph.register_conversion(
+            PayloadType::of::<bool>(),
+            PayloadType::of::<Payload>(),
+            |input| input * 10,
+        );
+        graph
+            .connect_input(input, sum.consumer(SumConsumer::In1))
+            .unwrap();
+        graph
+            .connect_output(gate.producer(GateProducer), output)
+            .unwrap();
+        graph.set_input(input, 1).unwrap();
+        graph.tick();
+        assert_eq!(graph.node(&sum).unwrap().read(SumProducer), 10);
+        assert_eq!(graph.output(output), Ok(10));
+    }
+
+    #[test]
+    fn return_error_on_occupied_graph_input_and_output() {
+        let mut graph = TestSignalGraph::new();
+        let input = graph.add_input("in", None).unwrap();
+        let output = graph.add_output("out", None).unwrap();
+        let one = graph.add_node(Generator(1));
+        let two = graph.add_node(Generator(2));
+        let recorder = graph.add_node(Recorder::default());
+        graph
+            .connect_input(input, recorder.consumer(RecorderConsumer))
+            .unwrap();
+        graph
+            .connect_output(one.producer(GeneratorProducer), output)
+            .unwrap();
+
+        assert_eq!(
+            graph.add_edge(
+                one.producer(GeneratorProducer),
+                recorder.consumer(RecorderConsumer)
+            ),
+            Err(AddEdgeError::ConsumerFedByInput {
+                producer: one.producer(GeneratorProducer),
+                consumer: recorder.consumer(RecorderConsumer),
+            })
+        );
+        assert_eq!(
+            graph.connect_output(two.producer(GeneratorProducer), output),
+            Err(Error::AddEdgeError(AddEdgeErrorKind::OccupiedConsumer))
+        );
+
+        graph.remove_input(input);
+        assert_eq!(
+            graph.connect_input(input, recorder.consumer(RecorderConsumer)),
+            Err(Error::StaleIndex)
         );
+        assert!(graph
+            .add_edge(
+                two.producer(GeneratorProducer),
+                recorder.consumer(RecorderConsumer)
+            )
+            .is_ok());
+    }
+
+    //
+    //    [Rec]
+    //      |
+    //    [Gate]
+    //
+    #[test]
+    fn add_edge_with_untyped_consumer() {
+        let mut graph = TestSignalGraph::new();
+        let gate = graph.add_node(Gate(true));
+        let recorder = graph.add_node(Recorder::default());
+
         graph.must_add_edge(
-            sum.producer(SumProducer),
-            recorder2.consumer(RecorderConsumer),
+            gate.producer(GateProducer),
+            recorder.consumer(RecorderConsumer),
         );
 
         graph.tick();
-        assert_eq!(graph.node(&recorder1).unwrap().read(RecorderProducer), 3);
-        assert_eq!(graph.node(&recorder2).unwrap().read(RecorderProducer), 3);
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
     }
 
     //
-    //  [Rec]    __
-    //      \   /  |
-    //       [+]   V
-    //      /   \__|
-    //    [1]
+    //    [Rec]
+    //      |
+    //     [+]
+    //      |
+    //    [Gate]
     //
     #[test]
-    fn tick_with_internal_cycle() {
+    fn tick_with_type_conversion() {
         let mut graph = TestSignalGraph::new();
-        let one = graph.add_node(Generator(1));
+        let gate = graph.add_node(Gate(true));
         let sum = graph.add_node(Sum::default());
         let recorder = graph.add_node(Recorder::default());
-        graph.must_add_edge(
-            one.producer(GeneratorProducer),
-            sum.consumer(SumConsumer::In1),
+        graph.register_conversion(
+            PayloadType::of::<bool>(),
+            PayloadType::of::<Payload>(),
+            |input| input * 10,
         );
-        graph.must_add_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
+        graph.must_add_edge(gate.producer(GateProducer), sum.consumer(SumConsumer::In1));
         graph.must_add_edge(
             sum.producer(SumProducer),
             recorder.consumer(RecorderConsumer),
         );
 
-        graph.tick();
-        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
-        graph.tick();
-        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 2);
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 10);
+    }
+
+    #[test]
+    fn get_node() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+
+        assert_eq!(graph.node(&one).unwrap().read(GeneratorProducer), 1);
+    }
+
+    #[test]
+    fn get_mutable_node() {
+        let mut graph = TestSignalGraph::new();
+        let recorder = graph.add_node(Recorder::default());
+
+        graph
+            .node_mut(&recorder)
+            .unwrap()
+            .write(RecorderConsumer, 10);
+
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 10);
     }
 
     //
@@ -1105,7 +5634,7 @@ mod tests {
     //     [1]
     //
     #[test]
-    fn check_for_edge() {
+    fn remove_node() {
         let mut graph = TestSignalGraph::new();
         let one = graph.add_node(Generator(1));
         let recorder = graph.add_node(Recorder::default());
@@ -1114,20 +5643,18 @@ mod tests {
             recorder.consumer(RecorderConsumer),
         );
 
-        assert!(graph.has_edge(
+        graph.remove_node(recorder);
+
+        assert!(!graph.has_edge(
             one.producer(GeneratorProducer),
             recorder.consumer(RecorderConsumer),
         ));
     }
 
-    //
-    //    [Rec]
-    //      |
-    //     [1]
-    //
     #[test]
-    fn remove_edge() {
+    fn undo_and_redo_edge_changes() {
         let mut graph = TestSignalGraph::new();
+        graph.enable_history();
         let one = graph.add_node(Generator(1));
         let recorder = graph.add_node(Recorder::default());
         graph.must_add_edge(
@@ -1135,188 +5662,524 @@ mod tests {
             recorder.consumer(RecorderConsumer),
         );
 
+        assert!(graph.undo());
+        assert!(!graph.has_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer)
+        ));
+
+        assert!(graph.redo());
+        assert!(graph.has_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer)
+        ));
+        assert!(!graph.can_redo());
+
         graph.remove_edge(
             one.producer(GeneratorProducer),
             recorder.consumer(RecorderConsumer),
         );
-
-        assert!(!graph.has_edge(
+        assert!(graph.undo());
+        assert!(graph.has_edge(
             one.producer(GeneratorProducer),
-            recorder.consumer(RecorderConsumer),
+            recorder.consumer(RecorderConsumer)
         ));
     }
 
-    //           __
-    //          /  |
-    //       [+]   V
-    //      /   \__|
-    //    [1]
+    //
+    //    [Rec]
+    //      |
+    //     [+]
+    //    /
+    //  [1]
     //
     #[test]
-    fn check_for_feedback_edge() {
+    fn undo_node_removal() {
         let mut graph = TestSignalGraph::new();
+        graph.enable_history();
         let one = graph.add_node(Generator(1));
         let sum = graph.add_node(Sum::default());
+        let recorder = graph.add_node(Recorder::default());
         graph.must_add_edge(
             one.producer(GeneratorProducer),
             sum.consumer(SumConsumer::In1),
         );
-        graph.must_add_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
+        graph.must_add_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+        );
+        graph.set_edge_transform(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer),
+            Scale(10),
+        );
+        graph.set_disconnect_policy(recorder.consumer(RecorderConsumer), DisconnectPolicy::Reset);
+        graph.tick();
 
-        assert!(graph.has_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2),));
-        assert!(!graph.feedback_edges.is_empty());
+        graph.remove_node(recorder);
+        assert!(graph.node(&recorder).is_none());
+        assert_eq!(
+            graph.disconnect_policy(recorder.consumer(RecorderConsumer)),
+            DisconnectPolicy::Keep
+        );
+
+        assert!(graph.undo());
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 0);
+        assert!(graph.has_edge_transform(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer)
+        ));
+        assert_eq!(
+            graph.disconnect_policy(recorder.consumer(RecorderConsumer)),
+            DisconnectPolicy::Reset
+        );
+
+        for _ in 0..4 {
+            assert!(graph.undo());
+        }
+        assert!(graph.node(&sum).is_none());
+        assert!(graph.node(&one).is_some());
+
+        for _ in 0..4 {
+            assert!(graph.redo());
+        }
+        assert!(graph.has_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer)
+        ));
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 10);
+    }
+
+    #[test]
+    fn undo_without_history() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        graph.remove_node(one);
+
+        assert!(!graph.can_undo());
+        assert!(!graph.undo());
+        assert!(graph.node(&one).is_none());
     }
 
-    //           __
-    //          /  |
-    //       [+]   V
-    //      /   \__|
-    //    [1]
+    //
+    //  [Rec]             [Rec]
+    //    |                 |
+    //   [1]   [2]   ->    [+]
+    //                     / \__
+    //                   [2]    |
+    //                     \____|
     //
     #[test]
-    fn remove_feedback_edge() {
+    fn apply_target_topology() {
         let mut graph = TestSignalGraph::new();
         let one = graph.add_node(Generator(1));
-        let sum = graph.add_node(Sum::default());
+        let two = graph.add_node(Generator(2));
+        let recorder = graph.add_node(Recorder(7));
         graph.must_add_edge(
             one.producer(GeneratorProducer),
-            sum.consumer(SumConsumer::In1),
+            recorder.consumer(RecorderConsumer),
         );
-        graph.must_add_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
 
-        graph.remove_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2));
+        let mut target = Target::new();
+        let kept_two = target.keep_node(two);
+        let kept_recorder = target.keep_node(recorder);
+        let sum = target.add_node(Sum::default());
+        target
+            .add_edge(kept_two, GeneratorProducer, sum, SumConsumer::In1)
+            .unwrap();
+        target
+            .add_edge(sum, SumProducer, sum, SumConsumer::In2)
+            .unwrap();
+        target
+            .add_edge(sum, SumProducer, kept_recorder, RecorderConsumer)
+            .unwrap();
+
+        let diff = graph.diff(&target);
+        assert_eq!(diff.removed_nodes(), [one]);
+        assert_eq!(diff.added_nodes(), 1);
+        assert!(diff.removed_edges().is_empty());
+        assert_eq!(diff.added_edges().len(), 3);
+
+        let new_nodes = graph.apply(target).unwrap();
+        assert_eq!(new_nodes.len(), 1);
+        let sum = new_nodes[0];
+        assert!(graph.node(&one).is_none());
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 7);
+        assert!(graph.is_feedback_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2)));
 
-        assert!(!graph.has_edge(sum.producer(SumProducer), sum.consumer(SumConsumer::In2),));
-        assert!(graph.feedback_edges.is_empty());
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 2);
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 4);
+
+        let mut target = Target::new();
+        target.keep_node(two);
+        target.keep_node(recorder);
+        target.keep_node(sum);
+        let diff = graph.diff(&target);
+        assert!(diff.removed_nodes().is_empty());
+        assert_eq!(diff.removed_edges().len(), 3);
     }
 
-    //           ___
-    //          /   |
-    //       [Rec]  |
-    // del ->  |    |
-    //       [Rec]  V
-    //         |    |
-    //        [+]   |
-    //       /   \__|
-    //      [1]
-    //
     #[test]
-    fn remove_redundant_feedback_edge() {
+    fn refuse_target_without_changing_graph() {
         let mut graph = TestSignalGraph::new();
         let one = graph.add_node(Generator(1));
-        let sum = graph.add_node(Sum::default());
-        let recorder1 = graph.add_node(Recorder::default());
-        let recorder2 = graph.add_node(Recorder::default());
+        let recorder = graph.add_node(Recorder::default());
+        let removed = graph.add_node(Generator(3));
         graph.must_add_edge(
             one.producer(GeneratorProducer),
-            sum.consumer(SumConsumer::In1),
-        );
-        graph.must_add_edge(
-            sum.producer(SumProducer),
-            recorder1.consumer(RecorderConsumer),
+            recorder.consumer(RecorderConsumer),
         );
-        graph.must_add_edge(
-            recorder1.producer(RecorderProducer),
-            recorder2.consumer(RecorderConsumer),
+        graph.remove_node(removed);
+
+        let mut target = Target::new();
+        let kept_one = target.keep_node(one);
+        let kept_recorder = target.keep_node(recorder);
+        let two = target.add_node(Generator(2));
+        target
+            .add_edge(kept_one, GeneratorProducer, kept_recorder, RecorderConsumer)
+            .unwrap();
+        target
+            .add_edge(two, GeneratorProducer, kept_recorder, RecorderConsumer)
+            .unwrap();
+        assert_eq!(
+            graph.apply(target),
+            Err(Error::AddEdgeError(AddEdgeErrorKind::OccupiedConsumer))
         );
+
+        let mut target = Target::new();
+        target.keep_node(removed);
+        assert_eq!(graph.apply(target), Err(Error::StaleIndex));
+
+        assert_eq!(graph.nodes().count(), 2);
+        assert!(graph.has_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer)
+        ));
+    }
+
+    #[test]
+    fn undo_applied_target_at_once() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let recorder = graph.add_node(Recorder::default());
         graph.must_add_edge(
-            recorder2.producer(RecorderProducer),
-            sum.consumer(SumConsumer::In2),
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer),
         );
+        graph.enable_history();
+
+        let mut target = Target::new();
+        let kept_recorder = target.keep_node(recorder);
+        let two = target.add_node(Generator(2));
+        target
+            .add_edge(two, GeneratorProducer, kept_recorder, RecorderConsumer)
+            .unwrap();
+        let two = graph.apply(target).unwrap()[0];
+
+        assert!(graph.undo());
+        assert!(!graph.can_undo());
+        assert!(graph.node(&two).is_none());
+        assert!(graph.has_edge(
+            one.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer)
+        ));
 
-        assert!(!graph.feedback_edges.is_empty());
+        assert!(graph.redo());
+        assert!(graph.node(&one).is_none());
+        assert!(graph.has_edge(
+            two.producer(GeneratorProducer),
+            recorder.consumer(RecorderConsumer)
+        ));
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 2);
+    }
 
-        graph.remove_edge(
-            recorder1.producer(RecorderProducer),
-            recorder2.consumer(RecorderConsumer),
-        );
+    #[test]
+    fn undo_applied_target_removing_cycle() {
+        let mut graph = TestSignalGraph::new();
+        let a = graph.add_node(Sum::default());
+        let b = graph.add_node(Sum::default());
+        let one = graph.add_node(Generator(1));
+        graph.must_add_edge(a.producer(SumProducer), b.consumer(SumConsumer::In1));
+        graph.must_add_edge(b.producer(SumProducer), a.consumer(SumConsumer::In1));
+        graph.enable_history();
+
+        let mut target = Target::new();
+        target.keep_node(a);
+        target.keep_node(b);
+        graph.apply(target).unwrap();
+        assert!(graph.node(&one).is_none());
+
+        assert!(graph.undo());
+        assert!(graph.node(&one).is_some());
+        assert!(graph.has_edge(a.producer(SumProducer), b.consumer(SumConsumer::In1)));
+        assert!(graph.is_feedback_edge(b.producer(SumProducer), a.consumer(SumConsumer::In1)));
+        graph.tick();
+    }
 
-        assert!(graph.feedback_edges.is_empty());
+    #[test]
+    fn refuse_invalid_port_in_target() {
+        let mut target: Target<TestNode, TestNodeIndex> = Target::new();
+        let one = target.add_node(Generator(1));
+        let recorder = target.add_node(Recorder::default());
+
+        assert!(matches!(
+            target.add_edge(one, SumProducer, recorder, RecorderConsumer),
+            Err(Error::InvalidPort)
+        ));
+        assert!(matches!(
+            target.add_edge(one, GeneratorProducer, NodeRef::New(5), RecorderConsumer),
+            Err(Error::NodeNotFound)
+        ));
+        assert!(target.edges().is_empty());
     }
 
-    //             ___
-    //            /   |
-    //         [Rec]  |
-    //           |    |
-    //         [Rec]  V
-    //           |    |
-    //          [+]   |
-    //  del -> /   \__|
-    //        [1]
+    //          ___
+    //         /   |
+    //      [Rec]  |
+    //        |    V      __
+    //       [+]   |     |  |
+    //      /   \__|    [+] |
+    //    [1]            |__|
     //
     #[test]
-    fn do_not_remove_needed_feedback_edge() {
+    fn list_feedback_loops() {
         let mut graph = TestSignalGraph::new();
         let one = graph.add_node(Generator(1));
         let sum = graph.add_node(Sum::default());
-        let recorder1 = graph.add_node(Recorder::default());
-        let recorder2 = graph.add_node(Recorder::default());
+        let recorder = graph.add_node(Recorder::default());
+        let accumulator = graph.add_node(Sum::default());
         graph.must_add_edge(
             one.producer(GeneratorProducer),
             sum.consumer(SumConsumer::In1),
         );
         graph.must_add_edge(
             sum.producer(SumProducer),
-            recorder1.consumer(RecorderConsumer),
+            recorder.consumer(RecorderConsumer),
         );
         graph.must_add_edge(
-            recorder1.producer(RecorderProducer),
-            recorder2.consumer(RecorderConsumer),
+            recorder.producer(RecorderProducer),
+            sum.consumer(SumConsumer::In2),
         );
         graph.must_add_edge(
-            recorder2.producer(RecorderProducer),
-            sum.consumer(SumConsumer::In2),
+            accumulator.producer(SumProducer),
+            accumulator.consumer(SumConsumer::In1),
         );
-        let original_feedbacks = graph.feedback_edges.len();
 
-        graph.remove_edge(
-            one.producer(GeneratorProducer),
-            sum.consumer(SumConsumer::In1),
+        let feedback_loops = graph.feedback_loops();
+        assert_eq!(feedback_loops.len(), 2);
+
+        assert_eq!(feedback_loops[0].nodes(), [sum, recorder]);
+        assert_eq!(
+            feedback_loops[0].edges(),
+            [
+                (
+                    sum.producer(SumProducer),
+                    recorder.consumer(RecorderConsumer)
+                ),
+                (
+                    recorder.producer(RecorderProducer),
+                    sum.consumer(SumConsumer::In2)
+                ),
+            ]
         );
-        assert_eq!(graph.feedback_edges.len(), original_feedbacks);
+        assert_eq!(
+            feedback_loops[0].feedback(),
+            (
+                recorder.producer(RecorderProducer),
+                sum.consumer(SumConsumer::In2)
+            )
+        );
+
+        assert_eq!(feedback_loops[1].nodes(), [accumulator]);
+        assert_eq!(feedback_loops[1].edges().len(), 1);
     }
 
     #[test]
-    fn get_node() {
+    fn tick_independent_nodes_in_stable_order() {
         let mut graph = TestSignalGraph::new();
-        let one = graph.add_node(Generator(1));
-
-        assert_eq!(graph.node(&one).unwrap().read(GeneratorProducer), 1);
+        let nodes: Vec<_> = (0..10).map(|i| graph.add_node(Generator(i))).collect();
+        let registered_order = |graph: &TestSignalGraph| -> Vec<TestNodeIndex> {
+            graph
+                .sorted_nodes
+                .iter()
+                .map(|node_index| *node_index.must_registered())
+                .collect()
+        };
+
+        assert_eq!(registered_order(&graph), nodes);
+
+        graph.set_tick_priority(nodes[7], 1);
+        graph.set_tick_priority(nodes[3], -1);
+        assert_eq!(graph.tick_priority(nodes[7]), 1);
+        let order = registered_order(&graph);
+        assert_eq!(order[0], nodes[7]);
+        assert_eq!(order[9], nodes[3]);
+        assert_eq!(order[1], nodes[0]);
     }
 
     #[test]
-    fn get_mutable_node() {
+    fn order_nodes_by_constraints() {
         let mut graph = TestSignalGraph::new();
-        let recorder = graph.add_node(Recorder::default());
+        let one = graph.add_node(Generator(1));
+        let two = graph.add_node(Generator(2));
+        let position = |graph: &TestSignalGraph, node_index: TestNodeIndex| {
+            graph
+                .sorted_nodes
+                .iter()
+                .position(|n| *n == SignalNodeIndex::Registered(node_index))
+                .unwrap()
+        };
+        assert!(position(&graph, one) < position(&graph, two));
+
+        graph.add_order_constraint(two, one).unwrap();
+        assert!(graph.has_order_constraint(two, one));
+        assert!(position(&graph, two) < position(&graph, one));
+
+        graph.remove_order_constraint(two, one);
+        assert!(position(&graph, one) < position(&graph, two));
+    }
 
-        graph
-            .node_mut(&recorder)
-            .unwrap()
-            .write(RecorderConsumer, 10);
+    #[test]
+    fn return_error_on_conflicting_order_constraints() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let two = graph.add_node(Generator(2));
+        let three = graph.add_node(Generator(3));
+        graph.add_order_constraint(one, two).unwrap();
+        graph.add_order_constraint(two, three).unwrap();
+
+        let error = graph.add_order_constraint(three, one).unwrap_err();
+        match error {
+            OrderConstraintError::Cycle { cycle, .. } => {
+                let nodes = cycle.nodes();
+                assert_eq!(nodes.len(), 3);
+                assert!(nodes.contains(&one) && nodes.contains(&two) && nodes.contains(&three));
+                assert_eq!(cycle.edges().len(), 3);
+                assert!(cycle.edges().contains(&(three, one)));
+            }
+            _ => panic!("Expected a cycle"),
+        }
+        assert!(!graph.has_order_constraint(three, one));
 
-        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 10);
+        graph.remove_node(three);
+        assert!(matches!(
+            graph.add_order_constraint(three, one),
+            Err(OrderConstraintError::NodeNotFound { .. })
+        ));
+        assert!(!graph.has_order_constraint(two, three));
     }
 
     //
     //    [Rec]
-    //      |
+    //      |   ^
+    //     [+]  : ordered before
+    //      |   :
     //     [1]
     //
     #[test]
-    fn remove_node() {
+    fn return_error_on_order_constraint_conflicting_with_edge() {
         let mut graph = TestSignalGraph::new();
         let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
         let recorder = graph.add_node(Recorder::default());
         graph.must_add_edge(
             one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.must_add_edge(
+            sum.producer(SumProducer),
             recorder.consumer(RecorderConsumer),
         );
 
-        graph.remove_node(recorder);
+        assert_eq!(
+            graph.add_order_constraint(recorder, sum),
+            Err(OrderConstraintError::DirectEdgeConflict {
+                before: recorder,
+                after: sum
+            })
+        );
 
-        assert!(!graph.has_edge(
+        assert!(!graph.has_order_constraint(recorder, sum));
+        assert!(!graph.is_feedback_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer)
+        ));
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
+    }
+
+    //
+    //    [Rec]
+    //      |   ^
+    //     [+]  : ordered before
+    //      |   :
+    //     [1]
+    //
+    #[test]
+    fn delay_edge_added_against_order_constraint() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let sum = graph.add_node(Sum::default());
+        let recorder = graph.add_node(Recorder::default());
+        graph.must_add_edge(
             one.producer(GeneratorProducer),
+            sum.consumer(SumConsumer::In1),
+        );
+        graph.add_order_constraint(recorder, sum).unwrap();
+
+        graph.must_add_edge(
+            sum.producer(SumProducer),
             recorder.consumer(RecorderConsumer),
+        );
+
+        assert!(graph.is_feedback_edge(
+            sum.producer(SumProducer),
+            recorder.consumer(RecorderConsumer)
         ));
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 0);
+        graph.tick();
+        assert_eq!(graph.node(&recorder).unwrap().read(RecorderProducer), 1);
+    }
+
+    #[test]
+    fn undo_order_constraint_changes() {
+        let mut graph = TestSignalGraph::new();
+        let one = graph.add_node(Generator(1));
+        let two = graph.add_node(Generator(2));
+        graph.enable_history();
+        graph.enable_event_queue();
+
+        graph.add_order_constraint(two, one).unwrap();
+        graph.remove_order_constraint(two, one);
+        let events: Vec<_> = graph
+            .drain_events()
+            .filter(|event| *event != Event::OrderRecomputed)
+            .collect();
+        assert_eq!(
+            events,
+            [
+                Event::OrderConstraintAdded(two, one),
+                Event::OrderConstraintRemoved(two, one)
+            ]
+        );
+
+        assert!(graph.undo());
+        assert!(graph.has_order_constraint(two, one));
+        assert!(graph.undo());
+        assert!(!graph.has_order_constraint(two, one));
+        assert!(graph.redo());
+        assert!(graph.has_order_constraint(two, one));
+
+        graph.remove_node(two);
+        assert!(graph
+            .drain_events()
+            .any(|event| event == Event::OrderConstraintRemoved(two, one)));
+        assert!(graph.undo());
+        assert!(graph.has_order_constraint(two, one));
     }
 }
@@ -1,58 +1,147 @@
-use alloc::collections::VecDeque;
+use alloc::collections::BinaryHeap;
+use alloc::vec;
 use alloc::vec::Vec;
+use core::cmp::Reverse;
 use core::hash::Hash;
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
 
-#[derive(Debug, Clone, Copy)]
-pub struct Cycle;
+/// A cycle preventing nodes from being ordered.
+///
+/// It is reported by
+/// [`OrderConstraintError::Cycle`](../signal/enum.OrderConstraintError.html#variant.Cycle).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N> {
+    pub(crate) nodes: Vec<N>,
+    pub(crate) edges: Vec<(N, N)>,
+}
+
+impl<N> Cycle<N> {
+    /// Nodes forming the cycle in the order of the flow.
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// Edges forming the cycle, each leading from a node to the next one. The
+    /// last edge leads back to the first node.
+    pub fn edges(&self) -> &[(N, N)] {
+        &self.edges
+    }
+}
 
-pub fn topological_sort<N, NI, EI>(nodes: NI, edges: EI) -> Result<Vec<N>, Cycle>
+/// Sort nodes so each of them comes after all nodes it depends on.
+///
+/// Nodes which do not depend on each other are ordered by the given key,
+/// lower first, so the result does not depend on iteration order of hash sets.
+pub fn topological_sort<N, NI, EI, K, F>(nodes: NI, edges: EI, key: F) -> Result<Vec<N>, Cycle<N>>
 where
     N: Copy + Hash + Eq,
     NI: IntoIterator<Item = N>,
     EI: IntoIterator<Item = (N, N)>,
+    K: Ord,
+    F: Fn(&N) -> K,
 {
-    let mut sorted_nodes: Vec<N> = Vec::new();
-
-    let mut edges: HashSet<(N, N)> = edges.into_iter().collect();
-
-    let mut nodes_queue: VecDeque<N> = {
-        let nodes: HashSet<_> = nodes.into_iter().collect();
-        let consumers: HashSet<_> = edges.iter().map(|(_, consumer)| *consumer).collect();
-        let pure_producers = nodes.difference(&consumers);
-        pure_producers.copied().collect()
+    let nodes: Vec<N> = {
+        let mut seen = HashSet::new();
+        nodes
+            .into_iter()
+            .filter(|node| seen.insert(*node))
+            .collect()
     };
+    let positions: HashMap<N, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(position, node)| (*node, position))
+        .collect();
+    let edges: HashSet<(N, N)> = edges.into_iter().collect();
 
-    while let Some(node) = nodes_queue.pop_front() {
-        sorted_nodes.push(node);
-
-        let outcoming_edges: HashSet<_> = edges
-            .iter()
-            .filter(|(source, _)| *source == node)
-            .copied()
-            .collect();
-
-        outcoming_edges.into_iter().for_each(|edge| {
-            let remote_node = edge.1;
+    let mut incoming_count = vec![0; nodes.len()];
+    let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    for (source, destination) in edges.iter() {
+        incoming_count[positions[destination]] += 1;
+        outgoing[positions[source]].push(positions[destination]);
+    }
 
-            edges.remove(&edge);
+    let mut queue: BinaryHeap<Reverse<(K, usize)>> = incoming_count
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count == 0)
+        .map(|(position, _)| Reverse((key(&nodes[position]), position)))
+        .collect();
 
-            let no_other_incoming_edges = edges
-                .iter()
-                .filter(|(_, destination)| *destination == remote_node)
-                .count()
-                == 0;
-            if no_other_incoming_edges {
-                nodes_queue.push_back(remote_node);
+    let mut sorted_nodes: Vec<N> = Vec::with_capacity(nodes.len());
+    while let Some(Reverse((_, position))) = queue.pop() {
+        sorted_nodes.push(nodes[position]);
+        for destination in outgoing[position].iter() {
+            incoming_count[*destination] -= 1;
+            if incoming_count[*destination] == 0 {
+                queue.push(Reverse((key(&nodes[*destination]), *destination)));
             }
-        });
+        }
     }
 
-    if edges.is_empty() {
+    if sorted_nodes.len() == nodes.len() {
         Ok(sorted_nodes)
     } else {
-        Err(Cycle)
+        let sorted: HashSet<_> = sorted_nodes.into_iter().collect();
+        let remaining_edges = edges
+            .into_iter()
+            .filter(|(source, _)| !sorted.contains(source))
+            .collect();
+        Err(find_cycle(&remaining_edges))
+    }
+}
+
+/// Check whether the destination can be reached from the source by following
+/// edges listed as successors of each node. Every node reaches itself.
+pub fn reaches<N>(successors: &HashMap<N, Vec<N>>, source: N, destination: N) -> bool
+where
+    N: Copy + Hash + Eq,
+{
+    let mut visited = HashSet::new();
+    let mut stack = vec![source];
+    while let Some(node) = stack.pop() {
+        if node == destination {
+            return true;
+        }
+        if visited.insert(node) {
+            if let Some(next) = successors.get(&node) {
+                stack.extend(next.iter().copied());
+            }
+        }
     }
+    false
+}
+
+// Every node left unsorted has an incoming edge from another unsorted node.
+// Walking these edges backwards must therefore eventually revisit a node,
+// closing a cycle.
+fn find_cycle<N>(remaining_edges: &HashSet<(N, N)>) -> Cycle<N>
+where
+    N: Copy + Hash + Eq,
+{
+    let predecessors: HashMap<N, N> = remaining_edges
+        .iter()
+        .map(|(source, destination)| (*destination, *source))
+        .collect();
+
+    let mut path = Vec::new();
+    let mut visited = HashSet::new();
+    let mut node = remaining_edges.iter().next().unwrap().1;
+    while visited.insert(node) {
+        path.push(node);
+        node = predecessors[&node];
+    }
+
+    let start = path.iter().position(|n| *n == node).unwrap();
+    let mut nodes: Vec<_> = path.split_off(start);
+    nodes.reverse();
+    let edges = nodes
+        .iter()
+        .zip(nodes.iter().cycle().skip(1))
+        .map(|(source, destination)| (*source, *destination))
+        .collect();
+
+    Cycle { nodes, edges }
 }
 
 #[cfg(test)]
@@ -74,7 +163,7 @@ mod tests {
         let mut second_group: HashSet<_> = vec![2, 3].into_iter().collect();
         let mut third_group: HashSet<_> = vec![4, 5].into_iter().collect();
 
-        let sorted_nodes = match topological_sort(nodes, edges) {
+        let sorted_nodes = match topological_sort(nodes, edges, |node| *node) {
             Ok(sorted_nodes) => sorted_nodes,
             Err(_) => panic!("Failed sorting nodes"),
         };
@@ -108,6 +197,20 @@ mod tests {
         third_group.remove(&item);
     }
 
+    ///  [3]  [1]  [2]  [0]
+    ///    \   |
+    ///     \  V
+    ///      [4]
+    #[test]
+    fn sort_independent_nodes_by_key() {
+        let nodes = vec![3, 1, 4, 2, 0];
+        let edges = vec![(3, 4), (1, 4)];
+
+        let sorted_nodes = topological_sort(nodes, edges, |node| *node).unwrap();
+
+        assert_eq!(sorted_nodes, [0, 1, 2, 3, 4]);
+    }
+
     ///      [0]
     ///  |  /   \  A
     ///  | |     | |
@@ -118,9 +221,48 @@ mod tests {
         let nodes: HashSet<_> = vec![0, 1].into_iter().collect();
         let edges = vec![(0, 1), (1, 0)];
 
-        match topological_sort(nodes, edges) {
-            Err(Cycle) => (),
+        match topological_sort(nodes, edges, |node| *node) {
+            Err(cycle) => {
+                assert_eq!(cycle.nodes.len(), 2);
+                assert_eq!(cycle.edges.len(), 2);
+                assert!(cycle.edges.contains(&(0, 1)));
+                assert!(cycle.edges.contains(&(1, 0)));
+            }
             Ok(_) => panic!("Must fail"),
         }
     }
+
+    ///  [0] -> [1] -> [2] -> [3] -> [4]
+    ///          A             |
+    ///          |_____________|
+    #[test]
+    fn find_cycle_among_other_nodes() {
+        let nodes: HashSet<_> = (0..=4).collect();
+        let edges = vec![(0, 1), (1, 2), (2, 3), (3, 4), (3, 1)];
+
+        let cycle = topological_sort(nodes, edges, |node| *node).unwrap_err();
+
+        assert_eq!(cycle.nodes.len(), 3);
+        let first = cycle.nodes.iter().position(|n| *n == 1).unwrap();
+        assert_eq!(cycle.nodes[(first + 1) % 3], 2);
+        assert_eq!(cycle.nodes[(first + 2) % 3], 3);
+        assert!(cycle.edges.contains(&(1, 2)));
+        assert!(cycle.edges.contains(&(2, 3)));
+        assert!(cycle.edges.contains(&(3, 1)));
+    }
+
+    ///  [0] -> [1] -> [2]    [3]
+    ///          |      A
+    ///          |______|
+    #[test]
+    fn check_reachability() {
+        let mut successors = HashMap::new();
+        successors.insert(0, vec![1]);
+        successors.insert(1, vec![2, 2]);
+
+        assert!(reaches(&successors, 0, 2));
+        assert!(reaches(&successors, 3, 3));
+        assert!(!reaches(&successors, 2, 0));
+        assert!(!reaches(&successors, 0, 3));
+    }
 }
@@ -0,0 +1,171 @@
+//! Transformations applied on data passing through an edge.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::hash::Hash;
+use core::ops::{Add, Mul};
+use hashbrown::HashMap;
+
+/// This trait is implemented by all transformations that can be attached to an
+/// edge of the signal graph.
+///
+/// The transformation is applied on the payload while it is being passed from
+/// the producer to the consumer. It is implemented for all closures taking and
+/// returning the payload, so custom mappings do not need to define their own
+/// type.
+///
+/// # Example
+///
+/// ```
+/// # use graphity::transform::{Scale, Transform};
+/// let mut double = Scale(2);
+/// assert_eq!(double.apply(10), 20);
+///
+/// let mut clip = |input: i32| input.min(15);
+/// assert_eq!(clip.apply(20), 15);
+/// ```
+pub trait Transform<T> {
+    /// Transform the payload passed through the edge.
+    fn apply(&mut self, input: T) -> T;
+}
+
+impl<T, F> Transform<T> for F
+where
+    F: FnMut(T) -> T,
+{
+    fn apply(&mut self, input: T) -> T {
+        self(input)
+    }
+}
+
+/// Multiply passed data by the given value.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Scale<T>(pub T);
+
+impl<T> Transform<T> for Scale<T>
+where
+    T: Mul<Output = T> + Copy,
+{
+    fn apply(&mut self, input: T) -> T {
+        input * self.0
+    }
+}
+
+/// Add the given value to passed data.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Offset<T>(pub T);
+
+impl<T> Transform<T> for Offset<T>
+where
+    T: Add<Output = T> + Copy,
+{
+    fn apply(&mut self, input: T) -> T {
+        input + self.0
+    }
+}
+
+// Transformations stored under their key in reusable slots. The graph resolves
+// keys to slots once after its topology changes, so `tick` reaches the
+// transformations by position only. Replacing a transformation keeps its slot.
+pub(crate) struct Slots<K, T> {
+    slots: Vec<Option<Box<dyn Transform<T>>>>,
+    keys: HashMap<K, usize>,
+    free: Vec<usize>,
+}
+
+impl<K, T> Slots<K, T>
+where
+    K: Hash + Eq,
+{
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            keys: HashMap::new(),
+            free: Vec::new(),
+        }
+    }
+
+    // Returns `true` if the key did not have a slot before.
+    pub fn insert(&mut self, key: K, transform: Box<dyn Transform<T>>) -> bool {
+        if let Some(slot) = self.keys.get(&key) {
+            self.slots[*slot] = Some(transform);
+            return false;
+        }
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot] = Some(transform);
+                slot
+            }
+            None => {
+                self.slots.push(Some(transform));
+                self.slots.len() - 1
+            }
+        };
+        self.keys.insert(key, slot);
+        true
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<Box<dyn Transform<T>>> {
+        let slot = self.keys.remove(key)?;
+        self.free.push(slot);
+        self.slots[slot].take()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.keys.contains_key(key)
+    }
+
+    pub fn slot(&self, key: &K) -> Option<usize> {
+        self.keys.get(key).copied()
+    }
+
+    pub fn get_mut(&mut self, slot: usize) -> Option<&mut Box<dyn Transform<T>>> {
+        self.slots.get_mut(slot).and_then(Option::as_mut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_i32() {
+        assert_eq!(Scale(3).apply(2), 6);
+    }
+
+    #[test]
+    fn offset_f32() {
+        assert_eq!(Offset(0.5).apply(1.0), 1.5);
+    }
+
+    #[test]
+    fn stateful_closure() {
+        let mut last = 0;
+        let mut difference = move |input: i32| {
+            let output = input - last;
+            last = input;
+            output
+        };
+
+        assert_eq!(difference.apply(5), 5);
+        assert_eq!(difference.apply(7), 2);
+    }
+
+    #[test]
+    fn reuse_slots_of_removed_transforms() {
+        let mut slots: Slots<&str, i32> = Slots::new();
+        assert!(slots.insert("a", Box::new(Scale(2))));
+        assert!(slots.insert("b", Box::new(Scale(3))));
+        assert!(!slots.insert("a", Box::new(Scale(4))));
+        assert_eq!(slots.slot(&"a"), Some(0));
+
+        assert!(slots.remove(&"a").is_some());
+        assert!(slots.get_mut(0).is_none());
+        assert!(!slots.contains_key(&"a"));
+
+        assert!(slots.insert("c", Box::new(Offset(1))));
+        assert_eq!(slots.slot(&"c"), Some(0));
+        assert_eq!(slots.get_mut(0).unwrap().apply(1), 2);
+        assert_eq!(slots.get_mut(1).unwrap().apply(1), 3);
+    }
+}